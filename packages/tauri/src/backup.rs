@@ -0,0 +1,233 @@
+//! Passphrase-encrypted export/import of the full config, secrets
+//! included. `try_migrate_electron_data` can only carry over non-secret
+//! settings; this is the real cross-device/full-backup path.
+//!
+//! Bundle layout (all fields authenticated, header also serves as AAD):
+//! `{ version, salt, argon2_params, nonce, ciphertext }` where
+//! `ciphertext` decrypts to `{ store: StoreData, secrets: [(key, value)] }`.
+
+use crate::storage::{keyring_key, set_secret, StorageState, StoreData};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+const BUNDLE_VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+
+/// Argon2 params persisted alongside the bundle so a future change to
+/// `Params::default()` (library upgrade, perf tweak) can never strand an
+/// already-exported bundle without the parameters it was actually
+/// encrypted with.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct Argon2Params {
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+impl Default for Argon2Params {
+    fn default() -> Self {
+        let p = Params::default();
+        Argon2Params { m_cost: p.m_cost(), t_cost: p.t_cost(), p_cost: p.p_cost() }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct BundleHeader {
+    version: u8,
+    salt: [u8; SALT_LEN],
+    argon2_params: Argon2Params,
+    nonce: [u8; 24],
+}
+
+#[derive(Serialize, Deserialize)]
+struct BundlePayload {
+    store: StoreData,
+    secrets: Vec<(String, String)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Bundle {
+    header: BundleHeader,
+    ciphertext: Vec<u8>,
+}
+
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8; SALT_LEN],
+    params: Argon2Params,
+) -> Result<[u8; 32], String> {
+    let argon2_params = Params::new(params.m_cost, params.t_cost, params.p_cost, Some(32))
+        .map_err(|e| format!("Invalid Argon2 params: {}", e))?;
+    let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, argon2_params);
+
+    let mut key = [0u8; 32];
+    argon2
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+/// Serialize the current config plus every secret it references into a
+/// single passphrase-encrypted file at `path`.
+#[tauri::command]
+pub fn export_config(
+    path: String,
+    passphrase: String,
+    storage: tauri::State<StorageState>,
+) -> Result<(), String> {
+    let store = {
+        let data = storage.data.lock().map_err(|e| e.to_string())?;
+        data.clone()
+    };
+
+    let mut secrets = Vec::new();
+    for source in store.source_identities() {
+        if source.source_type == "xtream" {
+            let key = keyring_key(&source.id, "password");
+            if let Some(value) = crate::storage::get_secret(&key) {
+                secrets.push((key, value));
+            }
+        }
+    }
+    for key in ["settings:tmdbApiKey", "settings:posterDbApiKey"] {
+        if let Some(value) = crate::storage::get_secret(key) {
+            secrets.push((key.to_string(), value));
+        }
+    }
+
+    let payload = BundlePayload { store, secrets };
+    let plaintext = serde_json::to_vec(&payload).map_err(|e| e.to_string())?;
+
+    let mut salt = [0u8; SALT_LEN];
+    use rand::RngCore;
+    rand::thread_rng().fill_bytes(&mut salt);
+    let argon2_params = Argon2Params::default();
+    let key = derive_key(&passphrase, &salt, argon2_params)?;
+
+    let cipher = XChaCha20Poly1305::new(&key.into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let header = BundleHeader { version: BUNDLE_VERSION, salt, argon2_params, nonce: nonce.into() };
+    let aad = serde_json::to_vec(&(header.version, header.salt, header.argon2_params, header.nonce))
+        .map_err(|e| e.to_string())?;
+
+    let ciphertext = cipher
+        .encrypt(&nonce, chacha20poly1305::aead::Payload { msg: &plaintext, aad: &aad })
+        .map_err(|e| format!("Encryption failed: {}", e))?;
+
+    let bundle = Bundle { header, ciphertext };
+    let bytes = serde_json::to_vec(&bundle).map_err(|e| e.to_string())?;
+    fs::write(PathBuf::from(path), bytes).map_err(|e| e.to_string())
+}
+
+/// Decrypt a bundle created by `export_config`, rewrite the JSON config,
+/// and repopulate the OS keyring with its secrets.
+#[tauri::command]
+pub fn import_config(
+    path: String,
+    passphrase: String,
+    app: AppHandle,
+    storage: tauri::State<StorageState>,
+) -> Result<(), String> {
+    let bytes = fs::read(PathBuf::from(path)).map_err(|e| e.to_string())?;
+    let bundle: Bundle = serde_json::from_slice(&bytes).map_err(|e| e.to_string())?;
+
+    if bundle.header.version != BUNDLE_VERSION {
+        return Err(format!("Unsupported bundle version {}", bundle.header.version));
+    }
+
+    let key = derive_key(&passphrase, &bundle.header.salt, bundle.header.argon2_params)?;
+    let cipher = XChaCha20Poly1305::new(&key.into());
+    let nonce = XNonce::from(bundle.header.nonce);
+
+    let aad = serde_json::to_vec(&(
+        bundle.header.version,
+        bundle.header.salt,
+        bundle.header.argon2_params,
+        bundle.header.nonce,
+    ))
+    .map_err(|e| e.to_string())?;
+    let plaintext = cipher
+        .decrypt(
+            &nonce,
+            chacha20poly1305::aead::Payload { msg: &bundle.ciphertext, aad: &aad },
+        )
+        .map_err(|_| "Decryption failed: wrong passphrase or corrupt bundle".to_string())?;
+
+    let payload: BundlePayload = serde_json::from_slice(&plaintext).map_err(|e| e.to_string())?;
+
+    for (key, value) in &payload.secrets {
+        if let Err(e) = set_secret(key, value) {
+            log::warn!("[BACKUP] Failed to restore secret {}: {}", key, e);
+        }
+    }
+
+    {
+        let mut data = storage.data.lock().map_err(|e| e.to_string())?;
+        *data = payload.store;
+    }
+    storage.save()?;
+
+    let _ = app; // reserved for a future "restart to apply" prompt
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derive_key_round_trips_with_persisted_params() {
+        let salt = [7u8; SALT_LEN];
+        let params = Argon2Params::default();
+
+        let key = derive_key("correct horse battery staple", &salt, params).unwrap();
+        let same_key = derive_key("correct horse battery staple", &salt, params).unwrap();
+        assert_eq!(key, same_key, "same passphrase/salt/params must derive the same key");
+
+        let other_key = derive_key("wrong passphrase", &salt, params).unwrap();
+        assert_ne!(key, other_key);
+    }
+
+    #[test]
+    fn derive_key_rejects_a_different_persisted_params() {
+        let salt = [7u8; SALT_LEN];
+        let params = Argon2Params::default();
+        let mut other_params = params;
+        other_params.t_cost += 1;
+
+        let key = derive_key("passphrase", &salt, params).unwrap();
+        let other_key = derive_key("passphrase", &salt, other_params).unwrap();
+        assert_ne!(key, other_key, "changing t_cost must change the derived key");
+    }
+
+    #[test]
+    fn bundle_round_trips_through_encrypt_and_decrypt() {
+        let salt = [3u8; SALT_LEN];
+        let params = Argon2Params::default();
+        let key = derive_key("pw", &salt, params).unwrap();
+
+        let cipher = XChaCha20Poly1305::new(&key.into());
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let header = BundleHeader { version: BUNDLE_VERSION, salt, argon2_params: params, nonce: nonce.into() };
+        let aad = serde_json::to_vec(&(header.version, header.salt, header.argon2_params, header.nonce)).unwrap();
+
+        let plaintext = b"secret payload".to_vec();
+        let ciphertext = cipher
+            .encrypt(&nonce, chacha20poly1305::aead::Payload { msg: &plaintext, aad: &aad })
+            .unwrap();
+
+        let decrypt_key = derive_key("pw", &header.salt, header.argon2_params).unwrap();
+        let decrypt_cipher = XChaCha20Poly1305::new(&decrypt_key.into());
+        let decrypted = decrypt_cipher
+            .decrypt(&nonce, chacha20poly1305::aead::Payload { msg: &ciphertext, aad: &aad })
+            .unwrap();
+
+        assert_eq!(decrypted, plaintext);
+    }
+}
@@ -0,0 +1,268 @@
+//! Generic async TTL cache keyed by (source id, resource kind). EPG and
+//! VOD catalog fetches both need to honor `epg_refresh_hours` /
+//! `vod_refresh_hours`, but nothing previously enforced those settings -
+//! the frontend just refetched on every load. Concurrent lookups for the
+//! same key coalesce onto one in-flight fetch via a per-key lock, and
+//! `fetched_at` timestamps (plus the cached value) persist to disk so
+//! TTLs survive a restart instead of resetting to "always stale".
+
+use crate::storage::StorageState;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::Manager;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ResourceKind {
+    Epg,
+    VodCatalog,
+}
+
+impl ResourceKind {
+    fn persist_file(self) -> &'static str {
+        match self {
+            ResourceKind::Epg => "cache-epg.json",
+            ResourceKind::VodCatalog => "cache-vod.json",
+        }
+    }
+
+    fn default_ttl_hours(self) -> u32 {
+        match self {
+            ResourceKind::Epg => 6,
+            ResourceKind::VodCatalog => 24,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Entry<V> {
+    fetched_at: u64,
+    value: V,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// One resource kind's cache: an async-locked map of key -> per-key slot.
+/// Looking up a key locks only that key's slot, so a burst of lookups for
+/// the same key serializes onto the same fetch instead of each one
+/// hitting the network; lookups for different keys never block each other.
+pub(crate) struct TtlCache<V> {
+    persist_path: PathBuf,
+    slots: Mutex<HashMap<String, Arc<Mutex<Option<Entry<V>>>>>>,
+}
+
+impl<V: Clone + Serialize + DeserializeOwned> TtlCache<V> {
+    pub(crate) fn new(persist_path: PathBuf) -> Self {
+        let persisted: HashMap<String, Entry<V>> = fs::read_to_string(&persist_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        let slots = persisted
+            .into_iter()
+            .map(|(key, entry)| (key, Arc::new(Mutex::new(Some(entry)))))
+            .collect();
+
+        Self { persist_path, slots: Mutex::new(slots) }
+    }
+
+    async fn slot(&self, key: &str) -> Arc<Mutex<Option<Entry<V>>>> {
+        let mut slots = self.slots.lock().await;
+        slots
+            .entry(key.to_string())
+            .or_insert_with(|| Arc::new(Mutex::new(None)))
+            .clone()
+    }
+
+    pub(crate) async fn get_or_fetch<F, Fut>(
+        &self,
+        key: &str,
+        ttl: Duration,
+        force_refresh: bool,
+        fetch: F,
+    ) -> Result<V, String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, String>>,
+    {
+        let slot = self.slot(key).await;
+        let mut guard = slot.lock().await;
+
+        if !force_refresh {
+            if let Some(entry) = guard.as_ref() {
+                if now().saturating_sub(entry.fetched_at) < ttl.as_secs() {
+                    return Ok(entry.value.clone());
+                }
+            }
+        }
+
+        let value = fetch().await?;
+        *guard = Some(Entry { fetched_at: now(), value: value.clone() });
+        drop(guard);
+        self.persist().await;
+        Ok(value)
+    }
+
+    async fn age(&self, key: &str) -> Option<Duration> {
+        let slot = self.slot(key).await;
+        let guard = slot.lock().await;
+        guard
+            .as_ref()
+            .map(|e| Duration::from_secs(now().saturating_sub(e.fetched_at)))
+    }
+
+    async fn invalidate(&self, key: &str) {
+        let slot = self.slot(key).await;
+        *slot.lock().await = None;
+        self.persist().await;
+    }
+
+    async fn persist(&self) {
+        let slots = self.slots.lock().await;
+        let mut snapshot = HashMap::new();
+        for (key, slot) in slots.iter() {
+            if let Some(entry) = slot.lock().await.clone() {
+                snapshot.insert(key.clone(), entry);
+            }
+        }
+        drop(slots);
+
+        if let Ok(json) = serde_json::to_string_pretty(&snapshot) {
+            let _ = fs::write(&self.persist_path, json);
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct StorageResult<T: Serialize> {
+    pub success: Option<bool>,
+    pub error: Option<String>,
+    pub data: Option<T>,
+}
+
+impl<T: Serialize> StorageResult<T> {
+    fn ok(data: T) -> Self {
+        Self { success: Some(true), error: None, data: Some(data) }
+    }
+
+    fn err(msg: impl Into<String>) -> Self {
+        Self { success: Some(false), error: Some(msg.into()), data: None }
+    }
+}
+
+pub struct CacheState {
+    epg: TtlCache<String>,
+    vod: TtlCache<String>,
+}
+
+impl CacheState {
+    fn cache(&self, kind: ResourceKind) -> &TtlCache<String> {
+        match kind {
+            ResourceKind::Epg => &self.epg,
+            ResourceKind::VodCatalog => &self.vod,
+        }
+    }
+}
+
+fn cache_key(source_id: &str, kind: ResourceKind) -> String {
+    format!("{}:{:?}", source_id, kind)
+}
+
+fn ttl_for(kind: ResourceKind, storage: &StorageState) -> Duration {
+    let hours = {
+        let data = storage.data.lock().unwrap();
+        match kind {
+            ResourceKind::Epg => data.settings.epg_refresh_hours,
+            ResourceKind::VodCatalog => data.settings.vod_refresh_hours,
+        }
+    };
+    Duration::from_secs(hours.unwrap_or(kind.default_ttl_hours()) as u64 * 3600)
+}
+
+pub fn init(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    let app_data = app
+        .path()
+        .app_data_dir()
+        .expect("Failed to get app data dir");
+
+    app.manage(CacheState {
+        epg: TtlCache::new(app_data.join(ResourceKind::Epg.persist_file())),
+        vod: TtlCache::new(app_data.join(ResourceKind::VodCatalog.persist_file())),
+    });
+
+    Ok(())
+}
+
+/// Fetches `url` through the TTL cache for `(source_id, resource)`, or
+/// returns the cached body if it's still within the refresh-hours window
+/// for that resource kind. `force_refresh` bypasses the TTL check.
+#[tauri::command]
+pub async fn cache_fetch(
+    source_id: String,
+    resource: ResourceKind,
+    url: String,
+    force_refresh: bool,
+    cache: tauri::State<'_, CacheState>,
+    storage: tauri::State<'_, StorageState>,
+    http: tauri::State<'_, crate::fetch_proxy::HttpClient>,
+) -> Result<StorageResult<String>, ()> {
+    let ttl = ttl_for(resource, &storage);
+    let key = cache_key(&source_id, resource);
+    let allow_lan = {
+        let data = storage.data.lock().unwrap();
+        data.settings.allow_lan_sources.unwrap_or(false)
+    };
+
+    let result = cache
+        .cache(resource)
+        .get_or_fetch(&key, ttl, force_refresh, || async {
+            crate::fetch_proxy::validated_fetch(&http.0, "GET", url, &None, &None, allow_lan)
+                .await?
+                .text()
+                .await
+                .map_err(|e| e.to_string())
+        })
+        .await;
+
+    Ok(match result {
+        Ok(body) => StorageResult::ok(body),
+        Err(e) => StorageResult::err(e),
+    })
+}
+
+/// Age (in seconds) of the currently cached value for `(source_id,
+/// resource)`, or `None` if nothing has been fetched yet.
+#[tauri::command]
+pub async fn cache_get_age(
+    source_id: String,
+    resource: ResourceKind,
+    cache: tauri::State<'_, CacheState>,
+) -> Result<StorageResult<Option<u64>>, ()> {
+    let key = cache_key(&source_id, resource);
+    let age = cache.cache(resource).age(&key).await.map(|d| d.as_secs());
+    Ok(StorageResult::ok(age))
+}
+
+/// Drops the cached value for `(source_id, resource)` so the next
+/// `cache_fetch` always refetches, regardless of TTL.
+#[tauri::command]
+pub async fn cache_invalidate(
+    source_id: String,
+    resource: ResourceKind,
+    cache: tauri::State<'_, CacheState>,
+) -> Result<StorageResult<()>, ()> {
+    let key = cache_key(&source_id, resource);
+    cache.cache(resource).invalidate(&key).await;
+    Ok(StorageResult::ok(()))
+}
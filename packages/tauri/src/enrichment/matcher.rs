@@ -0,0 +1,112 @@
+//! Title normalization and fuzzy matching used to pick the best TMDB
+//! search result for a raw playlist entry title like
+//! "The.Matrix.1999.1080p.BluRay.x264-GROUP".
+
+use super::tmdb::SearchResult;
+
+const QUALITY_TAGS: &[&str] = &[
+    "2160p", "1080p", "720p", "480p", "4k", "hdr", "hdr10", "sdr", "bluray", "blu-ray", "webrip",
+    "web-dl", "webdl", "hdtv", "dvdrip", "x264", "x265", "h264", "h265", "hevc", "aac", "ac3",
+    "dts", "multi", "dual-audio", "dubbed", "subbed",
+];
+
+pub struct NormalizedTitle {
+    pub title: String,
+    pub year: Option<u32>,
+}
+
+/// Strips quality tags, release-group suffixes, and language markers, and
+/// pulls out a plausible release year so it doesn't pollute the title
+/// used for similarity scoring.
+pub fn normalize_title(raw: &str) -> NormalizedTitle {
+    let mut text = raw.replace(['.', '_'], " ");
+
+    let year = extract_year(&text);
+    if let Some(y) = year {
+        text = text.replace(&y.to_string(), " ");
+    }
+
+    // Drop a trailing "-RELEASEGROUP" suffix.
+    if let Some(pos) = text.rfind('-') {
+        if pos + 1 < text.len() && text[pos + 1..].chars().all(|c| c.is_alphanumeric()) {
+            text.truncate(pos);
+        }
+    }
+
+    let words: Vec<&str> = text
+        .split_whitespace()
+        .filter(|w| {
+            let stripped = w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase();
+            !QUALITY_TAGS.contains(&stripped.as_str())
+        })
+        .collect();
+
+    NormalizedTitle { title: words.join(" ").trim().to_lowercase(), year }
+}
+
+fn extract_year(text: &str) -> Option<u32> {
+    let bytes = text.as_bytes();
+    for i in 0..bytes.len().saturating_sub(3) {
+        if bytes[i..i + 4].iter().all(|b| b.is_ascii_digit()) {
+            let before_ok = i == 0 || !bytes[i - 1].is_ascii_digit();
+            let after_ok = i + 4 == bytes.len() || !bytes[i + 4].is_ascii_digit();
+            if before_ok && after_ok {
+                if let Ok(y) = text[i..i + 4].parse::<u32>() {
+                    if (1900..=2099).contains(&y) {
+                        return Some(y);
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Normalized edit-distance similarity in `[0.0, 1.0]`, 1.0 being identical.
+fn similarity(a: &str, b: &str) -> f32 {
+    let a = a.to_lowercase();
+    let b = b.to_lowercase();
+    let max_len = a.chars().count().max(b.chars().count()).max(1);
+    1.0 - (levenshtein(&a, &b) as f32 / max_len as f32)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0usize; b.len() + 1];
+
+    for i in 1..=a.len() {
+        curr[0] = i;
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+/// Combines title similarity with year proximity (within 5 years); a
+/// missing year on either side scores as neutral rather than penalizing.
+fn score_candidate(query: &NormalizedTitle, candidate: &SearchResult) -> f32 {
+    let title_score = similarity(&query.title, &candidate.title);
+    let year_score = match (query.year, candidate.year) {
+        (Some(qy), Some(cy)) => (1.0 - ((qy as i32 - cy as i32).unsigned_abs() as f32 / 5.0)).max(0.0),
+        _ => 0.5,
+    };
+    title_score * 0.75 + year_score * 0.25
+}
+
+/// Best-scoring candidate, or `None` if nothing clears the match threshold.
+pub fn best_match<'a>(
+    query: &NormalizedTitle,
+    candidates: &'a [SearchResult],
+) -> Option<&'a SearchResult> {
+    candidates
+        .iter()
+        .map(|c| (c, score_candidate(query, c)))
+        .filter(|(_, score)| *score > 0.4)
+        .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(c, _)| c)
+}
@@ -0,0 +1,39 @@
+//! TMDB metadata enrichment: normalize a raw playlist title, search TMDB,
+//! pick the best match by title/year score, and cache the result. Split
+//! into `tmdb` (API client), `matcher` (normalization + scoring), and
+//! `scanner` (orchestration + the `scan_source_metadata` command) so
+//! RPDB/poster-db backdrop lookups can plug additional fields onto the
+//! same `EnrichedRecord` without touching the TMDB-specific pieces.
+
+mod matcher;
+mod scanner;
+mod tmdb;
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+pub use scanner::{scan_source_metadata, EnrichedRecord};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MediaType {
+    Movie,
+    Series,
+}
+
+pub struct EnrichmentState {
+    cache: crate::cache::TtlCache<EnrichedRecord>,
+}
+
+pub fn init(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    let app_data = app
+        .path()
+        .app_data_dir()
+        .expect("Failed to get app data dir");
+
+    app.manage(EnrichmentState {
+        cache: crate::cache::TtlCache::new(app_data.join("cache-tmdb-enrichment.json")),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,172 @@
+//! Orchestrates a scan: normalize each entry's title, resolve it against
+//! TMDB, and cache the result so re-scanning a source is cheap. Results
+//! are cached for a week - metadata rarely changes day to day.
+
+use super::matcher::{best_match, normalize_title};
+use super::tmdb::{Details, TmdbClient};
+use super::{EnrichmentState, MediaType};
+use crate::storage::StorageState;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+const ENRICHMENT_TTL: Duration = Duration::from_secs(7 * 24 * 3600);
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanEntry {
+    pub id: String,
+    pub raw_title: String,
+    pub media_type: MediaType,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnrichedRecord {
+    pub entry_id: String,
+    pub tmdb_id: Option<u32>,
+    pub title: String,
+    pub overview: String,
+    pub genre_ids: Vec<u32>,
+    pub poster_path: Option<String>,
+    pub backdrop_path: Option<String>,
+    pub runtime_minutes: Option<u32>,
+    pub hidden_by_genre_filter: bool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScanProgress {
+    source_id: String,
+    completed: usize,
+    total: usize,
+}
+
+#[derive(Serialize)]
+pub struct StorageResult<T: Serialize> {
+    pub success: Option<bool>,
+    pub error: Option<String>,
+    pub data: Option<T>,
+}
+
+impl<T: Serialize> StorageResult<T> {
+    fn ok(data: T) -> Self {
+        Self { success: Some(true), error: None, data: Some(data) }
+    }
+
+    fn err(msg: impl Into<String>) -> Self {
+        Self { success: Some(false), error: Some(msg.into()), data: None }
+    }
+}
+
+fn unmatched(entry: &ScanEntry) -> EnrichedRecord {
+    EnrichedRecord {
+        entry_id: entry.id.clone(),
+        tmdb_id: None,
+        title: entry.raw_title.clone(),
+        overview: String::new(),
+        genre_ids: Vec::new(),
+        poster_path: None,
+        backdrop_path: None,
+        runtime_minutes: None,
+        hidden_by_genre_filter: false,
+    }
+}
+
+fn from_details(entry: &ScanEntry, details: Details, enabled_genres: &Option<Vec<u32>>) -> EnrichedRecord {
+    let hidden = match enabled_genres {
+        Some(enabled) if !enabled.is_empty() => !details.genre_ids.iter().any(|g| enabled.contains(g)),
+        _ => false,
+    };
+
+    EnrichedRecord {
+        entry_id: entry.id.clone(),
+        tmdb_id: Some(details.id),
+        title: details.title,
+        overview: details.overview,
+        genre_ids: details.genre_ids,
+        poster_path: details.poster_path,
+        backdrop_path: details.backdrop_path,
+        runtime_minutes: details.runtime_minutes,
+        hidden_by_genre_filter: hidden,
+    }
+}
+
+async fn enrich_one(
+    client: &TmdbClient<'_>,
+    enabled_genres: &Option<Vec<u32>>,
+    entry: &ScanEntry,
+) -> EnrichedRecord {
+    let normalized = normalize_title(&entry.raw_title);
+
+    let candidates = match client.search(entry.media_type, &normalized.title, normalized.year).await {
+        Ok(c) => c,
+        Err(e) => {
+            log::warn!("[ENRICH] TMDB search failed for '{}': {}", entry.raw_title, e);
+            return unmatched(entry);
+        }
+    };
+
+    let Some(matched) = best_match(&normalized, &candidates) else {
+        return unmatched(entry);
+    };
+
+    match client.details(entry.media_type, matched.id).await {
+        Ok(details) => from_details(entry, details, enabled_genres),
+        Err(e) => {
+            log::warn!("[ENRICH] TMDB details failed for tmdb:{}: {}", matched.id, e);
+            unmatched(entry)
+        }
+    }
+}
+
+/// Resolves every entry in `entries` against TMDB and returns the
+/// enriched records, emitting an `enrichment-progress` event after each
+/// one so the frontend can show scan progress.
+#[tauri::command]
+pub async fn scan_source_metadata(
+    source_id: String,
+    entries: Vec<ScanEntry>,
+    app: AppHandle,
+    enrichment: tauri::State<'_, EnrichmentState>,
+    storage: tauri::State<'_, StorageState>,
+    http: tauri::State<'_, crate::fetch_proxy::HttpClient>,
+) -> Result<StorageResult<Vec<EnrichedRecord>>, ()> {
+    let Some(api_key) = crate::storage::get_secret("settings:tmdbApiKey") else {
+        return Ok(StorageResult::err("No TMDB API key configured in Settings"));
+    };
+
+    let (movie_genres, series_genres) = {
+        let data = storage.data.lock().unwrap();
+        (data.settings.movie_genres_enabled.clone(), data.settings.series_genres_enabled.clone())
+    };
+
+    let client = TmdbClient::new(&http.0, api_key);
+    let total = entries.len();
+    let mut records = Vec::with_capacity(total);
+
+    for (i, entry) in entries.iter().enumerate() {
+        let enabled_genres = match entry.media_type {
+            MediaType::Movie => &movie_genres,
+            MediaType::Series => &series_genres,
+        };
+
+        let cache_key = format!("{}:{}", source_id, entry.id);
+        let record = enrichment
+            .cache
+            .get_or_fetch(&cache_key, ENRICHMENT_TTL, false, || async {
+                Ok(enrich_one(&client, enabled_genres, entry).await)
+            })
+            .await
+            .unwrap_or_else(|_| unmatched(entry));
+
+        let _ = app.emit(
+            "enrichment-progress",
+            ScanProgress { source_id: source_id.clone(), completed: i + 1, total },
+        );
+
+        records.push(record);
+    }
+
+    Ok(StorageResult::ok(records))
+}
@@ -0,0 +1,145 @@
+//! Thin TMDB v3 client - just the search and details calls the enrichment
+//! scanner needs. Movie and TV responses use different field names for the
+//! same concepts (`title`/`release_date` vs `name`/`first_air_date`); the
+//! raw response structs alias both onto one field so the rest of the
+//! module doesn't need to branch on media type to read them.
+
+use super::MediaType;
+use serde::Deserialize;
+
+const API_BASE: &str = "https://api.themoviedb.org/3";
+
+pub struct TmdbClient<'a> {
+    http: &'a reqwest::Client,
+    api_key: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub id: u32,
+    pub title: String,
+    pub year: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Details {
+    pub id: u32,
+    pub title: String,
+    pub overview: String,
+    pub genre_ids: Vec<u32>,
+    pub poster_path: Option<String>,
+    pub backdrop_path: Option<String>,
+    pub runtime_minutes: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    results: Vec<SearchResultRaw>,
+}
+
+#[derive(Deserialize)]
+struct SearchResultRaw {
+    id: u32,
+    #[serde(alias = "name")]
+    title: Option<String>,
+    #[serde(alias = "first_air_date")]
+    release_date: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GenreRaw {
+    id: u32,
+}
+
+#[derive(Deserialize)]
+struct DetailsRaw {
+    id: u32,
+    #[serde(alias = "name")]
+    title: Option<String>,
+    overview: Option<String>,
+    genres: Option<Vec<GenreRaw>>,
+    poster_path: Option<String>,
+    backdrop_path: Option<String>,
+    runtime: Option<u32>,
+    episode_run_time: Option<Vec<u32>>,
+}
+
+impl<'a> TmdbClient<'a> {
+    pub fn new(http: &'a reqwest::Client, api_key: String) -> Self {
+        Self { http, api_key }
+    }
+
+    pub async fn search(
+        &self,
+        media_type: MediaType,
+        query: &str,
+        year: Option<u32>,
+    ) -> Result<Vec<SearchResult>, String> {
+        let path = match media_type {
+            MediaType::Movie => "search/movie",
+            MediaType::Series => "search/tv",
+        };
+
+        let mut req = self
+            .http
+            .get(format!("{}/{}", API_BASE, path))
+            .query(&[("api_key", self.api_key.as_str()), ("query", query)]);
+        if let Some(y) = year {
+            let year_param = match media_type {
+                MediaType::Movie => "year",
+                MediaType::Series => "first_air_date_year",
+            };
+            req = req.query(&[(year_param, y.to_string())]);
+        }
+
+        let response: SearchResponse = req
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(response
+            .results
+            .into_iter()
+            .map(|r| SearchResult {
+                id: r.id,
+                title: r.title.unwrap_or_default(),
+                year: r
+                    .release_date
+                    .as_deref()
+                    .and_then(|d| d.get(0..4))
+                    .and_then(|y| y.parse().ok()),
+            })
+            .collect())
+    }
+
+    pub async fn details(&self, media_type: MediaType, id: u32) -> Result<Details, String> {
+        let path = match media_type {
+            MediaType::Movie => format!("movie/{}", id),
+            MediaType::Series => format!("tv/{}", id),
+        };
+
+        let raw: DetailsRaw = self
+            .http
+            .get(format!("{}/{}", API_BASE, path))
+            .query(&[("api_key", self.api_key.as_str())])
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(Details {
+            id: raw.id,
+            title: raw.title.unwrap_or_default(),
+            overview: raw.overview.unwrap_or_default(),
+            genre_ids: raw.genres.unwrap_or_default().into_iter().map(|g| g.id).collect(),
+            poster_path: raw.poster_path,
+            backdrop_path: raw.backdrop_path,
+            runtime_minutes: raw.runtime.or_else(|| raw.episode_run_time.and_then(|v| v.into_iter().next())),
+        })
+    }
+}
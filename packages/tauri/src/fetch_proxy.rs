@@ -107,6 +107,91 @@ fn is_blocked_url(url: &str) -> bool {
     false
 }
 
+const MAX_REDIRECTS: u8 = 10;
+
+/// Resolve a `Location` header against the URL that produced it. Handles
+/// both absolute and relative redirect targets.
+fn resolve_redirect(base: &Url, location: &str) -> Result<Url, String> {
+    base.join(location)
+        .map_err(|e| format!("Invalid redirect location '{}': {}", location, e))
+}
+
+/// Re-run the SSRF checks against a redirect target, honoring the same
+/// `allow_lan_sources` escape hatch as the initial request.
+async fn check_redirect_target(url: &str, allow_lan: bool) -> Result<(), String> {
+    if allow_lan {
+        return Ok(());
+    }
+
+    if is_blocked_url(url) {
+        return Err(format!(
+            "Blocked redirect to {}: local network access is disabled. Enable \"Allow LAN sources\" in Settings > Security if you trust this source.",
+            url
+        ));
+    }
+
+    if check_dns_rebinding(url).await.unwrap_or(false) {
+        return Err(format!(
+            "Blocked redirect to {}: DNS resolves to a private IP address (possible DNS rebinding attack).",
+            url
+        ));
+    }
+
+    Ok(())
+}
+
+/// Send a request and manually follow up to `MAX_REDIRECTS` 3xx hops,
+/// re-validating each redirect target against the SSRF checks before
+/// following it. The shared `HttpClient` has automatic redirects disabled
+/// for exactly this reason - reqwest would otherwise follow a `Location`
+/// header straight past `is_blocked_url`/`check_dns_rebinding`.
+async fn send_with_manual_redirects(
+    client: &Client,
+    method: &str,
+    mut url: String,
+    headers: &Option<std::collections::HashMap<String, String>>,
+    body: &Option<String>,
+    allow_lan: bool,
+) -> Result<reqwest::Response, String> {
+    for hop in 0..MAX_REDIRECTS {
+        if hop > 0 {
+            check_redirect_target(&url, allow_lan).await?;
+        }
+
+        let mut request = match method.to_uppercase().as_str() {
+            "POST" => client.post(&url),
+            "PUT" => client.put(&url),
+            "DELETE" => client.delete(&url),
+            "PATCH" => client.patch(&url),
+            _ => client.get(&url),
+        };
+        if let Some(h) = headers {
+            for (k, v) in h {
+                request = request.header(k.as_str(), v.as_str());
+            }
+        }
+        if let Some(b) = body {
+            request = request.body(b.clone());
+        }
+
+        let response = request.send().await.map_err(|e| format!("Fetch failed: {}", e))?;
+
+        if !response.status().is_redirection() {
+            return Ok(response);
+        }
+
+        let location = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| "Redirect response missing Location header".to_string())?;
+        let base = Url::parse(&url).map_err(|e| e.to_string())?;
+        url = resolve_redirect(&base, location)?.to_string();
+    }
+
+    Err(format!("Too many redirects (max {})", MAX_REDIRECTS))
+}
+
 async fn check_dns_rebinding(url: &str) -> Result<bool, String> {
     let parsed = Url::parse(url).map_err(|e| e.to_string())?;
     let host = parsed.host_str().ok_or("No host")?;
@@ -134,63 +219,60 @@ async fn check_dns_rebinding(url: &str) -> Result<bool, String> {
     }
 }
 
-#[tauri::command]
-pub async fn fetch_proxy(
+/// The SSRF-validated fetch path shared by `fetch_proxy` and anything else
+/// (e.g. `cache_fetch`) that needs to hit a source-configured URL: runs the
+/// blocked-host/DNS-rebinding checks, then sends the request following
+/// redirects manually so every hop gets re-validated too.
+pub(crate) async fn validated_fetch(
+    client: &Client,
+    method: &str,
     url: String,
-    options: Option<FetchOptions>,
-    client: tauri::State<'_, HttpClient>,
-    storage: tauri::State<'_, crate::storage::StorageState>,
-) -> Result<StorageResult<FetchProxyResponse>, ()> {
-    // Check SSRF protection
-    let allow_lan = {
-        let data = storage.data.lock().unwrap();
-        data.settings.allow_lan_sources.unwrap_or(false)
-    };
-
+    headers: &Option<std::collections::HashMap<String, String>>,
+    body: &Option<String>,
+    allow_lan: bool,
+) -> Result<reqwest::Response, String> {
     if !allow_lan {
         if is_blocked_url(&url) {
-            return Ok(StorageResult::err(
-                "Blocked: Local network access is disabled. Enable \"Allow LAN sources\" in Settings > Security if you trust this source.",
-            ));
+            return Err(
+                "Blocked: Local network access is disabled. Enable \"Allow LAN sources\" in Settings > Security if you trust this source.".to_string(),
+            );
         }
 
-        // DNS rebinding check
         match check_dns_rebinding(&url).await {
             Ok(true) => {
-                return Ok(StorageResult::err(
-                    "Blocked: DNS resolves to a private IP address (possible DNS rebinding attack).",
-                ));
+                return Err(
+                    "Blocked: DNS resolves to a private IP address (possible DNS rebinding attack).".to_string(),
+                );
             }
             Ok(false) => {}
             Err(_) => {} // Let reqwest handle DNS errors
         }
     }
 
+    send_with_manual_redirects(client, method, url, headers, body, allow_lan).await
+}
+
+#[tauri::command]
+pub async fn fetch_proxy(
+    url: String,
+    options: Option<FetchOptions>,
+    client: tauri::State<'_, HttpClient>,
+    storage: tauri::State<'_, crate::storage::StorageState>,
+) -> Result<StorageResult<FetchProxyResponse>, ()> {
+    let allow_lan = {
+        let data = storage.data.lock().unwrap();
+        data.settings.allow_lan_sources.unwrap_or(false)
+    };
+
     let method = options
         .as_ref()
         .and_then(|o| o.method.as_deref())
-        .unwrap_or("GET");
-
-    let mut request = match method.to_uppercase().as_str() {
-        "POST" => client.0.post(&url),
-        "PUT" => client.0.put(&url),
-        "DELETE" => client.0.delete(&url),
-        "PATCH" => client.0.patch(&url),
-        _ => client.0.get(&url),
-    };
-
-    if let Some(ref opts) = options {
-        if let Some(ref headers) = opts.headers {
-            for (k, v) in headers {
-                request = request.header(k.as_str(), v.as_str());
-            }
-        }
-        if let Some(ref body) = opts.body {
-            request = request.body(body.clone());
-        }
-    }
+        .unwrap_or("GET")
+        .to_string();
+    let headers = options.as_ref().and_then(|o| o.headers.clone());
+    let body = options.as_ref().and_then(|o| o.body.clone());
 
-    match request.send().await {
+    match validated_fetch(&client.0, &method, url, &headers, &body, allow_lan).await {
         Ok(response) => {
             let status = response.status();
             let status_text = status.canonical_reason().unwrap_or("").to_string();
@@ -207,7 +289,7 @@ pub async fn fetch_proxy(
                 Err(e) => Ok(StorageResult::err(format!("Failed to read response: {}", e))),
             }
         }
-        Err(e) => Ok(StorageResult::err(format!("Fetch failed: {}", e))),
+        Err(e) => Ok(StorageResult::err(e)),
     }
 }
 
@@ -216,18 +298,10 @@ pub async fn fetch_binary(
     url: String,
     client: tauri::State<'_, HttpClient>,
 ) -> Result<StorageResult<String>, ()> {
-    if !is_allowed_binary_url(&url) {
-        let host = Url::parse(&url)
-            .ok()
-            .and_then(|u| u.host_str().map(String::from))
-            .unwrap_or_else(|| "unknown".to_string());
-        return Ok(StorageResult::err(format!(
-            "Domain not allowed for binary fetch: {}",
-            host
-        )));
-    }
-
-    match client.0.get(&url).send().await {
+    // The shared client has automatic redirects disabled (see
+    // `send_with_manual_redirects`), so follow manually here too -
+    // re-checking the domain allowlist on every hop.
+    match fetch_binary_following_redirects(&client.0, url).await {
         Ok(response) => {
             if !response.status().is_success() {
                 return Ok(StorageResult::err(format!(
@@ -245,6 +319,101 @@ pub async fn fetch_binary(
                 Err(e) => Ok(StorageResult::err(format!("Failed to read response: {}", e))),
             }
         }
-        Err(e) => Ok(StorageResult::err(format!("Fetch failed: {}", e))),
+        Err(e) => Ok(StorageResult::err(e)),
+    }
+}
+
+/// Fetch `url`, manually following up to `MAX_REDIRECTS` 3xx hops and
+/// re-checking the binary-fetch domain allowlist on every hop.
+async fn fetch_binary_following_redirects(
+    client: &Client,
+    mut url: String,
+) -> Result<reqwest::Response, String> {
+    for _ in 0..MAX_REDIRECTS {
+        if !is_allowed_binary_url(&url) {
+            let host = Url::parse(&url)
+                .ok()
+                .and_then(|u| u.host_str().map(String::from))
+                .unwrap_or_else(|| "unknown".to_string());
+            return Err(format!("Domain not allowed for binary fetch: {}", host));
+        }
+
+        let response = client.get(&url).send().await.map_err(|e| format!("Fetch failed: {}", e))?;
+
+        if !response.status().is_redirection() {
+            return Ok(response);
+        }
+
+        let location = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| "Redirect response missing Location header".to_string())?;
+        let base = Url::parse(&url).map_err(|e| e.to_string())?;
+        url = resolve_redirect(&base, location)?.to_string();
+    }
+
+    Err(format!("Too many redirects (max {})", MAX_REDIRECTS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_blocked_url_blocks_localhost_and_private_ips() {
+        assert!(is_blocked_url("http://localhost/"));
+        assert!(is_blocked_url("http://127.0.0.1/"));
+        assert!(is_blocked_url("http://192.168.1.1/"));
+        assert!(is_blocked_url("http://169.254.169.254/"), "cloud metadata address must be blocked");
+        assert!(is_blocked_url("file:///etc/passwd"));
+        assert!(is_blocked_url("not a url"));
+    }
+
+    #[test]
+    fn is_blocked_url_allows_public_hosts() {
+        assert!(!is_blocked_url("https://example.com/"));
+        assert!(!is_blocked_url("https://files.tmdb.org/t/p/original/x.jpg"));
+    }
+
+    #[test]
+    fn resolve_redirect_handles_absolute_and_relative_locations() {
+        let base = Url::parse("https://example.com/a/b").unwrap();
+
+        let absolute = resolve_redirect(&base, "https://other.example/c").unwrap();
+        assert_eq!(absolute.as_str(), "https://other.example/c");
+
+        let relative = resolve_redirect(&base, "/c").unwrap();
+        assert_eq!(relative.as_str(), "https://example.com/c");
+    }
+
+    #[test]
+    fn resolve_redirect_rejects_an_unparseable_location() {
+        let base = Url::parse("https://example.com/").unwrap();
+        assert!(resolve_redirect(&base, "http://[::1").is_err());
+    }
+
+    #[tokio::test]
+    async fn check_redirect_target_blocks_a_redirect_to_a_private_host() {
+        let err = check_redirect_target("http://127.0.0.1/admin", false).await.unwrap_err();
+        assert!(err.contains("Blocked redirect"), "unexpected error: {}", err);
+    }
+
+    #[tokio::test]
+    async fn check_redirect_target_allows_a_private_host_when_lan_is_allowed() {
+        assert!(check_redirect_target("http://127.0.0.1/admin", true).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn check_redirect_target_allows_a_public_host() {
+        assert!(check_redirect_target("https://example.com/", false).await.is_ok());
+    }
+
+    #[test]
+    fn is_allowed_binary_url_only_allows_the_tmdb_image_domains() {
+        assert!(is_allowed_binary_url("https://files.tmdb.org/t/p/original/x.jpg"));
+        assert!(is_allowed_binary_url("https://images.files.tmdb.org/t/p/original/x.jpg"));
+        assert!(!is_allowed_binary_url("https://evil.example/t/p/original/x.jpg"));
+        assert!(!is_allowed_binary_url("not a url"));
     }
 }
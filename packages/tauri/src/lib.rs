@@ -1,8 +1,14 @@
 use tauri::Manager;
 
+mod backup;
+mod cache;
+mod enrichment;
 mod fetch_proxy;
 mod mpv;
+mod p2p;
 mod platform;
+mod secret_store;
+mod shm_ring;
 mod storage;
 mod window_cmds;
 
@@ -13,8 +19,12 @@ pub fn run() {
         .plugin(tauri_plugin_os::init())
         .setup(|app| {
             // Initialize shared reqwest client for fetch proxy
+            // Redirects are followed manually in fetch_proxy so every hop can
+            // be re-checked against the SSRF allowlist (see
+            // send_with_manual_redirects).
             let client = reqwest::Client::builder()
                 .user_agent("sbtlTV/0.1.0")
+                .redirect(reqwest::redirect::Policy::none())
                 .build()
                 .expect("Failed to create HTTP client");
             app.manage(fetch_proxy::HttpClient(client));
@@ -22,6 +32,21 @@ pub fn run() {
             // Initialize storage
             storage::init(app)?;
 
+            // Initialize P2P sync identity
+            if let Err(e) = p2p::init(app) {
+                log::warn!("[P2P] Failed to initialize: {}", e);
+            }
+
+            // Initialize EPG/VOD TTL cache
+            cache::init(app)?;
+
+            // Initialize TMDB enrichment cache
+            enrichment::init(app)?;
+
+            // Initialize the software-decode shm frame ring (used only if
+            // the GPU render path can't get a GL context)
+            shm_ring::init(app);
+
             // Initialize mpv with offscreen rendering
             let handle = app.handle().clone();
             eprintln!("[sbtlTV] Initializing mpv...");
@@ -39,6 +64,10 @@ pub fn run() {
             window_cmds::window_close,
             window_cmds::window_get_size,
             window_cmds::window_set_size,
+            window_cmds::window_get_position,
+            window_cmds::window_set_position,
+            window_cmds::window_save_state,
+            window_cmds::window_restore_state,
             // Platform
             platform::get_platform,
             // Storage
@@ -49,10 +78,27 @@ pub fn run() {
             storage::get_settings,
             storage::update_settings,
             storage::is_encryption_available,
+            storage::get_secret_backend,
             storage::import_m3u_file,
+            // Backup / migration
+            backup::export_config,
+            backup::import_config,
+            // EPG/VOD TTL cache
+            cache::cache_fetch,
+            cache::cache_get_age,
+            cache::cache_invalidate,
+            // TMDB enrichment
+            enrichment::scan_source_metadata,
             // Fetch proxy
             fetch_proxy::fetch_proxy,
             fetch_proxy::fetch_binary,
+            // P2P sync
+            p2p::p2p_device_info,
+            p2p::p2p_generate_pairing_code,
+            p2p::p2p_pair_with_code,
+            p2p::p2p_sync_now,
+            p2p::p2p_start_listening,
+            p2p::p2p_stop_listening,
             // mpv
             mpv::mpv_load,
             mpv::mpv_play,
@@ -63,6 +109,39 @@ pub fn run() {
             mpv::mpv_toggle_mute,
             mpv::mpv_seek,
             mpv::mpv_get_status,
+            mpv::mpv_playlist_append,
+            mpv::mpv_playlist_next,
+            mpv::mpv_playlist_prev,
+            mpv::mpv_playlist_clear,
+            mpv::mpv_playlist_remove,
+            mpv::mpv_get_playlist,
+            // Track selection
+            mpv::mpv_get_tracks,
+            mpv::mpv_set_audio_track,
+            mpv::mpv_set_subtitle_track,
+            mpv::mpv_set_video_track,
+            mpv::mpv_add_subtitle,
+            // Adaptive-stream quality selection
+            mpv::mpv_get_qualities,
+            mpv::mpv_set_quality,
+            // Watch-party sync
+            mpv::mpv_sync_host,
+            mpv::mpv_sync_join,
+            mpv::mpv_sync_set_source,
+            mpv::mpv_sync_leave,
+            // Remote control
+            mpv::mpv_remote_start,
+            mpv::mpv_remote_stop,
+            mpv::mpv_remote_auth_token,
+            // Seek-bar thumbnails
+            mpv::mpv_thumbnail_at,
+            // PipeWire screencast (Linux only)
+            #[cfg(all(feature = "fbo-fallback", feature = "pipewire-screencast", target_os = "linux"))]
+            mpv::mpv_start_screencast,
+            #[cfg(all(feature = "fbo-fallback", feature = "pipewire-screencast", target_os = "linux"))]
+            mpv::mpv_stop_screencast,
+            // Software-decode shm frame ring (GL-less fallback)
+            shm_ring::shm_ring_get_handle,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
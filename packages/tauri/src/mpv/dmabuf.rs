@@ -0,0 +1,350 @@
+//! Linux-only zero-copy DMABUF export of the FBO render target.
+//!
+//! Exports the FBO's color attachment as a DMABUF handle via the Mesa EGL
+//! extension `EGL_MESA_image_dma_buf_export`, so a compositor, a Wayland
+//! subsurface, or another GPU-buffer consumer can import the GPU buffer
+//! directly instead of the render thread reading pixels back to host
+//! memory through `OffscreenRenderer::copy_into`. Only meaningful on a
+//! GBM/DRI EGL backend - software rasterizers and anything without the
+//! extension fail `export_texture` with a plain `Err`, so callers should
+//! treat this as a best-effort upgrade over the `FrameRingBuffer` CPU-copy
+//! path in `mod::render_thread_fbo`, not something to depend on.
+//!
+//! A DMABUF fd is only meaningful inside the process that owns it (or one
+//! it was explicitly shared with) - a frontend webview running in another
+//! process can't do anything with the bare integer other than waste it,
+//! and nothing was ever closing that integer either, so every exported
+//! frame leaked a fd. `DmabufFrame` now closes its fds on `Drop`, and the
+//! only supported way to hand them to a real consumer is
+//! `send_frame_to_consumer`, which passes them over a connected
+//! `UnixStream` via `SCM_RIGHTS` - the kernel mechanism for transferring
+//! fd ownership across a process boundary. The frontend only ever sees
+//! `DmabufFrameMeta` (size/stride/modifier, no fd) over the normal Tauri
+//! event channel, for display/debugging purposes.
+
+use gl::types::GLuint;
+use serde::Serialize;
+use std::ffi::{c_void, CString};
+use std::os::fd::RawFd;
+use std::os::raw::c_char;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::sync::Mutex;
+
+#[link(name = "EGL")]
+extern "C" {
+    fn eglGetCurrentDisplay() -> *mut c_void;
+    fn eglGetCurrentContext() -> *mut c_void;
+    fn eglGetProcAddress(procname: *const c_char) -> *mut c_void;
+}
+
+extern "C" {
+    fn close(fd: i32) -> i32;
+    fn sendmsg(sockfd: i32, msg: *const Msghdr, flags: i32) -> isize;
+}
+
+/// Mirrors the libc `iovec`/`msghdr`/`cmsghdr` layout on Linux - hand-rolled
+/// here rather than pulling in a libc binding crate, matching the rest of
+/// this file's direct-FFI approach to EGL.
+#[repr(C)]
+struct Iovec {
+    iov_base: *mut c_void,
+    iov_len: usize,
+}
+
+#[repr(C)]
+struct Msghdr {
+    msg_name: *mut c_void,
+    msg_namelen: u32,
+    msg_iov: *mut Iovec,
+    msg_iovlen: usize,
+    msg_control: *mut c_void,
+    msg_controllen: usize,
+    msg_flags: i32,
+}
+
+#[repr(C)]
+struct Cmsghdr {
+    cmsg_len: usize,
+    cmsg_level: i32,
+    cmsg_type: i32,
+}
+
+const SOL_SOCKET: i32 = 1;
+const SCM_RIGHTS: i32 = 1;
+
+type EglImageKhr = *mut c_void;
+
+type PfnEglCreateImageKhr =
+    unsafe extern "C" fn(*mut c_void, *mut c_void, u32, *mut c_void, *const isize) -> EglImageKhr;
+type PfnEglDestroyImageKhr = unsafe extern "C" fn(*mut c_void, EglImageKhr) -> u32;
+type PfnEglExportDmabufImageQueryMesa =
+    unsafe extern "C" fn(*mut c_void, EglImageKhr, *mut i32, *mut i32, *mut u64) -> u32;
+type PfnEglExportDmabufImageMesa =
+    unsafe extern "C" fn(*mut c_void, EglImageKhr, *mut i32, *mut i32, *mut i32) -> u32;
+
+const EGL_GL_TEXTURE_2D_KHR: u32 = 0x30B1;
+const EGL_GL_TEXTURE_LEVEL_KHR: isize = 0x30BC;
+const EGL_NONE: isize = 0x3038;
+
+unsafe fn load_ext<T>(name: &str) -> Option<T> {
+    let c_name = CString::new(name).unwrap();
+    let ptr = eglGetProcAddress(c_name.as_ptr());
+    if ptr.is_null() {
+        None
+    } else {
+        Some(std::mem::transmute_copy(&ptr))
+    }
+}
+
+/// One plane of an exported DMABUF - fd, byte stride, and byte offset
+/// within that fd for this plane's data. Not `Serialize`: the fd is only
+/// valid in this process, so it must never end up in a JSON event payload
+/// (see module docs) - only `DmabufPlaneMeta` is.
+pub struct DmabufPlane {
+    pub fd: RawFd,
+    pub stride: u32,
+    pub offset: u32,
+}
+
+/// A single exported frame, ready to be imported by a compositor (e.g. via
+/// `zwp_linux_dmabuf_v1`) without a CPU copy. Owns its plane fds: dropping
+/// a `DmabufFrame` without first handing it to `send_frame_to_consumer`
+/// closes them, so a frame nobody claims never leaks.
+pub struct DmabufFrame {
+    pub width: u32,
+    pub height: u32,
+    pub fourcc: u32,
+    pub modifier: u64,
+    pub planes: Vec<DmabufPlane>,
+}
+
+impl Drop for DmabufFrame {
+    fn drop(&mut self) {
+        for plane in &self.planes {
+            if plane.fd >= 0 {
+                unsafe { close(plane.fd) };
+            }
+        }
+    }
+}
+
+/// Byte layout/size info for one plane, with no fd - safe to serialize
+/// straight into a Tauri event for the frontend to display/debug against.
+#[derive(Clone, Serialize)]
+pub struct DmabufPlaneMeta {
+    pub stride: u32,
+    pub offset: u32,
+}
+
+/// Everything about a `DmabufFrame` except its fds. `frame_id` ties this
+/// back to the fds a consumer received separately over
+/// `send_frame_to_consumer`, since the two never travel together.
+#[derive(Clone, Serialize)]
+pub struct DmabufFrameMeta {
+    pub frame_id: u64,
+    pub width: u32,
+    pub height: u32,
+    pub fourcc: u32,
+    pub modifier: u64,
+    pub planes: Vec<DmabufPlaneMeta>,
+}
+
+impl DmabufFrame {
+    pub fn meta(&self, frame_id: u64) -> DmabufFrameMeta {
+        DmabufFrameMeta {
+            frame_id,
+            width: self.width,
+            height: self.height,
+            fourcc: self.fourcc,
+            modifier: self.modifier,
+            planes: self.planes.iter().map(|p| DmabufPlaneMeta { stride: p.stride, offset: p.offset }).collect(),
+        }
+    }
+}
+
+/// Export `texture` (the FBO's color attachment - see
+/// `OffscreenRenderer::color_texture`) as a `DmabufFrame`. Requires a
+/// current EGL display/context and the `EGL_MESA_image_dma_buf_export`
+/// extension; both are checked and reported as an `Err` rather than
+/// assumed.
+///
+/// # Safety
+/// Must be called on the render thread with the FBO's GL context current,
+/// and `texture` must name a live 2D texture on that context.
+pub unsafe fn export_texture(texture: GLuint, width: u32, height: u32) -> Result<DmabufFrame, String> {
+    let display = eglGetCurrentDisplay();
+    let context = eglGetCurrentContext();
+    if display.is_null() || context.is_null() {
+        return Err("No current EGL display/context".to_string());
+    }
+
+    let create_image: PfnEglCreateImageKhr = load_ext("eglCreateImageKHR").ok_or("eglCreateImageKHR not available")?;
+    let destroy_image: PfnEglDestroyImageKhr =
+        load_ext("eglDestroyImageKHR").ok_or("eglDestroyImageKHR not available")?;
+    let export_query: PfnEglExportDmabufImageQueryMesa =
+        load_ext("eglExportDMABUFImageQueryMESA").ok_or("EGL_MESA_image_dma_buf_export not supported")?;
+    let export_image: PfnEglExportDmabufImageMesa =
+        load_ext("eglExportDMABUFImageMESA").ok_or("EGL_MESA_image_dma_buf_export not supported")?;
+
+    let attribs = [EGL_GL_TEXTURE_LEVEL_KHR, 0, EGL_NONE];
+    let image = create_image(display, context, EGL_GL_TEXTURE_2D_KHR, texture as *mut c_void, attribs.as_ptr());
+    if image.is_null() {
+        return Err("eglCreateImageKHR failed".to_string());
+    }
+
+    let mut fourcc: i32 = 0;
+    let mut num_planes: i32 = 0;
+    let mut modifier: u64 = 0;
+    if export_query(display, image, &mut fourcc, &mut num_planes, &mut modifier) == 0 {
+        destroy_image(display, image);
+        return Err("eglExportDMABUFImageQueryMESA failed".to_string());
+    }
+
+    let num_planes = num_planes.max(1) as usize;
+    let mut fds = vec![-1i32; num_planes];
+    let mut strides = vec![0i32; num_planes];
+    let mut offsets = vec![0i32; num_planes];
+    let exported = export_image(display, image, fds.as_mut_ptr(), strides.as_mut_ptr(), offsets.as_mut_ptr());
+    destroy_image(display, image);
+    if exported == 0 {
+        return Err("eglExportDMABUFImageMESA failed".to_string());
+    }
+
+    let planes = fds
+        .into_iter()
+        .zip(strides)
+        .zip(offsets)
+        .map(|((fd, stride), offset)| DmabufPlane { fd, stride: stride as u32, offset: offset as u32 })
+        .collect();
+
+    Ok(DmabufFrame { width, height, fourcc: fourcc as u32, modifier, planes })
+}
+
+/// Hand `frame`'s plane fds to whatever is on the other end of `stream`
+/// via `SCM_RIGHTS`, the only mechanism that makes an fd number mean
+/// anything in another process. Writes a length-prefixed JSON
+/// `DmabufFrameMeta` first so the consumer can match the fds it receives
+/// (in the order `frame.planes` lists them) back up with stride/offset/
+/// size, then sends the fds themselves as ancillary data on a one-byte
+/// dummy payload (`sendmsg` requires at least one byte of real data
+/// alongside a control message on Linux).
+///
+/// Closes every plane fd before returning - via `frame`'s `Drop` impl -
+/// regardless of whether the send succeeds, so a frame is never leaked
+/// whether or not a consumer is attached to receive it.
+pub fn send_frame_to_consumer(stream: &UnixStream, frame: DmabufFrame, frame_id: u64) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let meta = frame.meta(frame_id);
+    let meta_json = serde_json::to_vec(&meta)?;
+    (&stream.try_clone()?).write_all(&(meta_json.len() as u32).to_be_bytes())?;
+    (&stream.try_clone()?).write_all(&meta_json)?;
+
+    let fds: Vec<i32> = frame.planes.iter().map(|p| p.fd).collect();
+    unsafe { send_fds(stream.as_raw_fd(), &fds) }
+}
+
+/// Raw `sendmsg` with an `SCM_RIGHTS` control message carrying `fds`. The
+/// control buffer layout follows `CMSG_SPACE`/`CMSG_LEN` on Linux: the
+/// `cmsghdr` header immediately followed by the fd array, with the total
+/// buffer rounded up to `usize` alignment.
+unsafe fn send_fds(sockfd: i32, fds: &[i32]) -> std::io::Result<()> {
+    let fds_bytes = std::mem::size_of_val(fds);
+    let cmsg_len = std::mem::size_of::<Cmsghdr>() + fds_bytes;
+    let align = std::mem::size_of::<usize>();
+    let controllen = (cmsg_len + align - 1) / align * align;
+
+    let mut control = vec![0u8; controllen];
+    {
+        let cmsg = control.as_mut_ptr() as *mut Cmsghdr;
+        (*cmsg).cmsg_len = cmsg_len;
+        (*cmsg).cmsg_level = SOL_SOCKET;
+        (*cmsg).cmsg_type = SCM_RIGHTS;
+        let data_ptr = control.as_mut_ptr().add(std::mem::size_of::<Cmsghdr>()) as *mut i32;
+        std::ptr::copy_nonoverlapping(fds.as_ptr(), data_ptr, fds.len());
+    }
+
+    let mut dummy = [0u8; 1];
+    let mut iov = Iovec { iov_base: dummy.as_mut_ptr() as *mut c_void, iov_len: dummy.len() };
+    let msg = Msghdr {
+        msg_name: std::ptr::null_mut(),
+        msg_namelen: 0,
+        msg_iov: &mut iov,
+        msg_iovlen: 1,
+        msg_control: control.as_mut_ptr() as *mut c_void,
+        msg_controllen: controllen,
+        msg_flags: 0,
+    };
+
+    if sendmsg(sockfd, &msg, 0) < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// The one consumer currently attached at `socket_path()`, if any.
+/// Single-slot rather than a list of clients: there's only one real
+/// consumer envisioned today (the local compositor), and `render_thread_fbo`
+/// would otherwise need to `sendmsg` the same frame to every client, which
+/// isn't worth the complexity until a second consumer actually shows up.
+pub struct DmabufConsumer {
+    socket: Mutex<Option<UnixStream>>,
+}
+
+impl DmabufConsumer {
+    pub fn new() -> Self {
+        Self { socket: Mutex::new(None) }
+    }
+
+    /// Socket consumers (e.g. the compositor helper) connect to, to start
+    /// receiving frames via `send_frame_to_consumer`.
+    pub fn socket_path() -> std::path::PathBuf {
+        let runtime_dir = std::env::var("XDG_RUNTIME_DIR").unwrap_or_else(|_| "/tmp".to_string());
+        std::path::Path::new(&runtime_dir).join("sbtltv-dmabuf.sock")
+    }
+
+    /// Accept (and replace) the current consumer connection, forever. Runs
+    /// on its own thread for the life of the app - there's no explicit
+    /// stop, same as `render_thread_fbo`'s render loop it feeds.
+    pub fn listen(self: std::sync::Arc<Self>) -> std::io::Result<()> {
+        let path = Self::socket_path();
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path)?;
+
+        std::thread::spawn(move || {
+            for incoming in listener.incoming() {
+                match incoming {
+                    Ok(stream) => {
+                        log::info!("[DMABUF] Consumer connected");
+                        *self.socket.lock().unwrap() = Some(stream);
+                    }
+                    Err(e) => log::warn!("[DMABUF] Accept failed: {}", e),
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Send `frame` to the attached consumer, if any; otherwise the frame
+    /// is simply dropped (closing its fds - see `DmabufFrame::drop`). A
+    /// send error most likely means the consumer disconnected, so the slot
+    /// is cleared rather than left pointing at a dead socket.
+    pub fn send(&self, frame: DmabufFrame, frame_id: u64) {
+        let mut guard = self.socket.lock().unwrap();
+        let Some(stream) = guard.as_ref() else {
+            return; // no consumer attached - frame drops here, fds close
+        };
+
+        if let Err(e) = send_frame_to_consumer(stream, frame, frame_id) {
+            log::warn!("[DMABUF] Consumer send failed, dropping connection: {}", e);
+            *guard = None;
+        }
+    }
+}
+
+impl Default for DmabufConsumer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
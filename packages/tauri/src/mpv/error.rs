@@ -0,0 +1,90 @@
+//! Typed error for the external-mpv path. `MpvIpcClient`, `find_mpv_binary`,
+//! and `ExternalMpv`'s methods all return this instead of a bare `String`
+//! so the frontend can tell "mpv isn't installed" (actionable: tell the
+//! user to install it) from "the command timed out" (transient: just
+//! retry) instead of pattern-matching on error text.
+
+use super::{MpvErrorPayload, MpvResult};
+use std::fmt;
+
+#[derive(Debug)]
+pub enum MpvError {
+    BinaryNotFound,
+    SpawnFailed(std::io::Error),
+    ConnectFailed(String),
+    Timeout,
+    CommandFailed { code: String, message: String },
+    PropertyUnavailable(String),
+    Serde(serde_json::Error),
+    /// The IPC connection dropped (mpv exited, socket closed) while this
+    /// request was in flight, or the reconnect supervisor gave up after
+    /// exhausting its retry budget. Distinguished from `Timeout` (the
+    /// connection may still be fine, mpv just hasn't answered yet) and
+    /// from `ConnectFailed` (the very first connection attempt) so
+    /// callers can tell "mpv restarted out from under you" apart from
+    /// either of those.
+    Disconnected,
+}
+
+impl MpvError {
+    pub(super) fn kind(&self) -> &'static str {
+        match self {
+            MpvError::BinaryNotFound => "binary_not_found",
+            MpvError::SpawnFailed(_) => "spawn_failed",
+            MpvError::ConnectFailed(_) => "connect_failed",
+            MpvError::Timeout => "timeout",
+            MpvError::CommandFailed { .. } => "command_failed",
+            MpvError::PropertyUnavailable(_) => "property_unavailable",
+            MpvError::Serde(_) => "serde",
+            MpvError::Disconnected => "disconnected",
+        }
+    }
+}
+
+impl fmt::Display for MpvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MpvError::BinaryNotFound => {
+                write!(f, "mpv not found - install mpv or check bundled resources")
+            }
+            MpvError::SpawnFailed(e) => write!(f, "Failed to spawn mpv: {}", e),
+            MpvError::ConnectFailed(msg) => write!(f, "Failed to connect to mpv: {}", msg),
+            MpvError::Timeout => write!(f, "mpv command timed out"),
+            MpvError::CommandFailed { code, message } => write!(f, "{} ({})", message, code),
+            MpvError::PropertyUnavailable(property) => {
+                write!(f, "Property '{}' unavailable", property)
+            }
+            MpvError::Serde(e) => write!(f, "Failed to (de)serialize mpv message: {}", e),
+            MpvError::Disconnected => write!(f, "Lost connection to mpv"),
+        }
+    }
+}
+
+impl std::error::Error for MpvError {}
+
+impl From<serde_json::Error> for MpvError {
+    fn from(e: serde_json::Error) -> Self {
+        MpvError::Serde(e)
+    }
+}
+
+impl From<MpvError> for MpvErrorPayload {
+    fn from(e: MpvError) -> Self {
+        Self { kind: e.kind(), message: e.to_string() }
+    }
+}
+
+impl From<MpvError> for MpvResult {
+    fn from(e: MpvError) -> Self {
+        Self { success: None, error: Some(e.into()) }
+    }
+}
+
+impl From<Result<(), MpvError>> for MpvResult {
+    fn from(result: Result<(), MpvError>) -> Self {
+        match result {
+            Ok(()) => MpvResult::ok(),
+            Err(e) => e.into(),
+        }
+    }
+}
@@ -3,12 +3,14 @@
 //! Windows: Spawns mpv with --wid to render directly into the app's HWND
 //! Linux: Spawns mpv as standalone window (optional power-user setting)
 
-use super::ipc::{MpvIpcClient, MpvEvent, start_reader_thread};
-use super::{MpvResult, MpvStatus};
-use serde_json::Value;
+use super::error::MpvError;
+use super::ipc::{MpvIpcClient, MpvEvent};
+use super::sync_party;
+use super::{MpvStatus, PlaylistEntry, QualityInfo, TrackInfo};
+use serde::Deserialize;
 use std::process::{Child, Command};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager};
 
@@ -28,8 +30,10 @@ fn get_socket_path() -> String {
     }
 }
 
-/// Find mpv binary - checks bundled location first, then system paths
-fn find_mpv_binary(app: &tauri::AppHandle) -> Option<String> {
+/// Find mpv binary - checks bundled location first, then system paths.
+/// `pub(super)` so the thumbnail subsystem's dedicated mpv instance (see
+/// `thumbnail::ThumbnailMpv`) can locate the same binary.
+pub(super) fn find_mpv_binary(app: &tauri::AppHandle) -> Result<String, MpvError> {
     // Try bundled mpv first (in resources/mpv/)
     if let Ok(resource_path) = app.path().resource_dir() {
         #[cfg(target_os = "windows")]
@@ -37,7 +41,7 @@ fn find_mpv_binary(app: &tauri::AppHandle) -> Option<String> {
             let bundled = resource_path.join("mpv").join("mpv.exe");
             if bundled.exists() {
                 log::info!("[MPV-EXT] Found bundled mpv: {:?}", bundled);
-                return Some(bundled.to_string_lossy().to_string());
+                return Ok(bundled.to_string_lossy().to_string());
             }
         }
 
@@ -46,7 +50,7 @@ fn find_mpv_binary(app: &tauri::AppHandle) -> Option<String> {
             let bundled = resource_path.join("mpv").join("MacOS").join("mpv");
             if bundled.exists() {
                 log::info!("[MPV-EXT] Found bundled mpv: {:?}", bundled);
-                return Some(bundled.to_string_lossy().to_string());
+                return Ok(bundled.to_string_lossy().to_string());
             }
         }
     }
@@ -63,7 +67,7 @@ fn find_mpv_binary(app: &tauri::AppHandle) -> Option<String> {
         for path in paths {
             if std::path::Path::new(path).exists() {
                 log::info!("[MPV-EXT] Found system mpv: {}", path);
-                return Some(path.to_string());
+                return Ok(path.to_string());
             }
         }
         // Check LOCALAPPDATA
@@ -71,10 +75,10 @@ fn find_mpv_binary(app: &tauri::AppHandle) -> Option<String> {
             let path = format!(r"{}\Programs\mpv\mpv.exe", local);
             if std::path::Path::new(&path).exists() {
                 log::info!("[MPV-EXT] Found user mpv: {}", path);
-                return Some(path);
+                return Ok(path);
             }
         }
-        None
+        Err(MpvError::BinaryNotFound)
     }
 
     #[cfg(target_os = "linux")]
@@ -83,10 +87,10 @@ fn find_mpv_binary(app: &tauri::AppHandle) -> Option<String> {
         for path in paths {
             if std::path::Path::new(path).exists() {
                 log::info!("[MPV-EXT] Found system mpv: {}", path);
-                return Some(path.to_string());
+                return Ok(path.to_string());
             }
         }
-        None
+        Err(MpvError::BinaryNotFound)
     }
 
     #[cfg(target_os = "macos")]
@@ -99,31 +103,129 @@ fn find_mpv_binary(app: &tauri::AppHandle) -> Option<String> {
         for path in paths {
             if std::path::Path::new(path).exists() {
                 log::info!("[MPV-EXT] Found system mpv: {}", path);
-                return Some(path.to_string());
+                return Ok(path.to_string());
             }
         }
-        None
+        Err(MpvError::BinaryNotFound)
     }
 }
 
+/// IDs passed to mpv's `observe_property` command. Arbitrary but must stay
+/// stable for the lifetime of a connection, since `property-change` events
+/// echo back the `name` rather than the id, so these only need to be
+/// distinct from each other.
+const PROP_PAUSE: u64 = 1;
+const PROP_VOLUME: u64 = 2;
+const PROP_MUTE: u64 = 3;
+const PROP_TIME_POS: u64 = 4;
+const PROP_DURATION: u64 = 5;
+const PROP_PLAYLIST: u64 = 6;
+const PROP_EOF_REACHED: u64 = 7;
+const PROP_CORE_IDLE: u64 = 8;
+const PROP_PLAYLIST_POS: u64 = 9;
+const PROP_PLAYLIST_COUNT: u64 = 10;
+
 /// External mpv process with IPC control
 pub struct ExternalMpv {
     process: Child,
     ipc: Arc<MpvIpcClient>,
     shutdown: Arc<AtomicBool>,
-    #[allow(dead_code)]
-    reader_handle: std::thread::JoinHandle<()>,
+    /// Status assembled incrementally from `property-change` events rather
+    /// than re-queried on every `get_status` poll; pushed to the frontend
+    /// as a whole via the `mpv-status` event whenever a field changes.
+    status_cache: Arc<Mutex<MpvStatus>>,
+    /// Kept so `Drop` can tear down the remote-control server alongside
+    /// this mpv session instead of leaving it running against a dead
+    /// player.
+    app: AppHandle,
+}
+
+/// Query every property that makes up `MpvStatus` directly. Used once to
+/// seed `status_cache` right after connecting, and by the on-demand
+/// `get_status` getter.
+fn status_snapshot(ipc: &MpvIpcClient) -> MpvStatus {
+    let playing = ipc.get_property("pause")
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_bool())
+        .map(|paused| !paused)
+        .unwrap_or(false);
+
+    let volume = ipc.get_property("volume")
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_f64())
+        .unwrap_or(100.0);
+
+    let muted = ipc.get_property("mute")
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let position = ipc.get_property("time-pos")
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_f64())
+        .unwrap_or(0.0);
+
+    let duration = ipc.get_property("duration")
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_f64())
+        .unwrap_or(0.0);
+
+    let playlist_pos = ipc.get_property("playlist-pos")
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_i64())
+        .unwrap_or(-1);
+
+    let playlist_count = ipc.get_property("playlist-count")
+        .ok()
+        .flatten()
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as usize;
+
+    MpvStatus {
+        playing,
+        volume,
+        muted,
+        position,
+        duration,
+        playlist_pos,
+        playlist_count,
+    }
+}
+
+/// Spawns the task that dispatches property-change events from the IPC
+/// client's broadcast channel into `handle_mpv_event`, for as long as
+/// `shutdown` stays false.
+fn spawn_event_dispatch(
+    ipc: &Arc<MpvIpcClient>,
+    app: AppHandle,
+    shutdown: Arc<AtomicBool>,
+    status_cache: Arc<Mutex<MpvStatus>>,
+) {
+    let mut events = ipc.subscribe_events();
+    tauri::async_runtime::spawn(async move {
+        while let Ok(event) = events.recv().await {
+            if shutdown.load(Ordering::SeqCst) {
+                break;
+            }
+            handle_mpv_event(&app, event, &status_cache);
+        }
+    });
 }
 
 impl ExternalMpv {
     /// Spawn mpv embedded in a window (Windows with --wid)
     #[cfg(target_os = "windows")]
-    pub fn new_embedded(window: &tauri::WebviewWindow, app: AppHandle) -> Result<Self, String> {
-        let hwnd = get_hwnd(window)?;
+    pub fn new_embedded(window: &tauri::WebviewWindow, app: AppHandle) -> Result<Self, MpvError> {
+        let hwnd = get_hwnd(window).map_err(MpvError::ConnectFailed)?;
         log::info!("[MPV-EXT] Got HWND: {}", hwnd);
 
-        let mpv_path = find_mpv_binary(&app)
-            .ok_or_else(|| "mpv not found - install mpv or check bundled resources".to_string())?;
+        let mpv_path = find_mpv_binary(&app)?;
         log::info!("[MPV-EXT] Using mpv: {}", mpv_path);
 
         let socket_path = get_socket_path();
@@ -156,7 +258,7 @@ impl ExternalMpv {
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
             .spawn()
-            .map_err(|e| format!("Failed to spawn mpv: {}", e))?;
+            .map_err(MpvError::SpawnFailed)?;
 
         log::info!("[MPV-EXT] mpv process spawned, PID: {}", process.id());
 
@@ -166,17 +268,25 @@ impl ExternalMpv {
         // Connect IPC
         let ipc = Arc::new(MpvIpcClient::connect(&socket_path)?);
         let shutdown = Arc::new(AtomicBool::new(false));
-
-        // WINDOWS: Skip reader thread - cloned pipe handle causes app hang
-        // The reader thread blocking on read somehow affects the main thread
-        // Commands still work via send_command_async, just no property events
-        log::warn!("[MPV-EXT] Windows: Skipping reader thread (causes app hang)");
-        let reader_handle = std::thread::spawn(|| {});
-
-        // Skip observe_property since we have no reader to process responses
-        log::info!("[MPV-EXT] Windows: Skipping property observers");
-
-        log::info!("[MPV-EXT] Initialized successfully (limited mode)");
+        let status_cache = Arc::new(Mutex::new(status_snapshot(&ipc)));
+
+        // The reader is now a cooperatively-scheduled Tokio task rather than
+        // a thread blocking on a cloned pipe handle, so it no longer hangs
+        // the app - property observers can stay enabled on Windows too.
+        spawn_event_dispatch(&ipc, app.clone(), shutdown.clone(), status_cache.clone());
+
+        ipc.observe_property(PROP_PAUSE, "pause")?;
+        ipc.observe_property(PROP_VOLUME, "volume")?;
+        ipc.observe_property(PROP_MUTE, "mute")?;
+        ipc.observe_property(PROP_TIME_POS, "time-pos")?;
+        ipc.observe_property(PROP_DURATION, "duration")?;
+        ipc.observe_property(PROP_PLAYLIST, "playlist")?;
+        ipc.observe_property(PROP_EOF_REACHED, "eof-reached")?;
+        ipc.observe_property(PROP_CORE_IDLE, "core-idle")?;
+        ipc.observe_property(PROP_PLAYLIST_POS, "playlist-pos")?;
+        ipc.observe_property(PROP_PLAYLIST_COUNT, "playlist-count")?;
+
+        log::info!("[MPV-EXT] Initialized successfully");
 
         // Emit ready event
         let _ = app.emit("mpv-ready", true);
@@ -185,15 +295,15 @@ impl ExternalMpv {
             process,
             ipc,
             shutdown,
-            reader_handle,
+            status_cache,
+            app,
         })
     }
 
     /// Spawn mpv as standalone window (Linux power-user mode)
     #[cfg(target_os = "linux")]
-    pub fn new_standalone(app: AppHandle) -> Result<Self, String> {
-        let mpv_path = find_mpv_binary(&app)
-            .ok_or_else(|| "mpv not found - install via package manager".to_string())?;
+    pub fn new_standalone(app: AppHandle) -> Result<Self, MpvError> {
+        let mpv_path = find_mpv_binary(&app)?;
         log::info!("[MPV-EXT] Using mpv: {}", mpv_path);
 
         let socket_path = get_socket_path();
@@ -222,7 +332,7 @@ impl ExternalMpv {
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
             .spawn()
-            .map_err(|e| format!("Failed to spawn mpv: {}", e))?;
+            .map_err(MpvError::SpawnFailed)?;
 
         log::info!("[MPV-EXT] mpv process spawned, PID: {}", process.id());
 
@@ -232,24 +342,21 @@ impl ExternalMpv {
         // Connect IPC
         let ipc = Arc::new(MpvIpcClient::connect(&socket_path)?);
         let shutdown = Arc::new(AtomicBool::new(false));
+        let status_cache = Arc::new(Mutex::new(status_snapshot(&ipc)));
 
-        // Start reader thread for events
-        let ipc_clone = ipc.clone();
-        let app_clone = app.clone();
-        let shutdown_clone = shutdown.clone();
-        let reader_handle = start_reader_thread(&socket_path, ipc_clone.clone(), move |event| {
-            if shutdown_clone.load(Ordering::SeqCst) {
-                return;
-            }
-            handle_mpv_event(&app_clone, event);
-        })?;
+        spawn_event_dispatch(&ipc, app.clone(), shutdown.clone(), status_cache.clone());
 
         // Observe properties
-        ipc.observe_property(1, "pause")?;
-        ipc.observe_property(2, "volume")?;
-        ipc.observe_property(3, "mute")?;
-        ipc.observe_property(4, "time-pos")?;
-        ipc.observe_property(5, "duration")?;
+        ipc.observe_property(PROP_PAUSE, "pause")?;
+        ipc.observe_property(PROP_VOLUME, "volume")?;
+        ipc.observe_property(PROP_MUTE, "mute")?;
+        ipc.observe_property(PROP_TIME_POS, "time-pos")?;
+        ipc.observe_property(PROP_DURATION, "duration")?;
+        ipc.observe_property(PROP_PLAYLIST, "playlist")?;
+        ipc.observe_property(PROP_EOF_REACHED, "eof-reached")?;
+        ipc.observe_property(PROP_CORE_IDLE, "core-idle")?;
+        ipc.observe_property(PROP_PLAYLIST_POS, "playlist-pos")?;
+        ipc.observe_property(PROP_PLAYLIST_COUNT, "playlist-count")?;
 
         log::info!("[MPV-EXT] Initialized successfully (standalone mode)");
 
@@ -260,126 +367,197 @@ impl ExternalMpv {
             process,
             ipc,
             shutdown,
-            reader_handle,
+            status_cache,
+            app,
         })
     }
 
     /// Load a media file
-    pub fn load(&self, url: &str) -> MpvResult {
-        match self.ipc.send_command(&["loadfile", url]) {
-            Ok(resp) if resp.error == "success" => MpvResult::ok(),
-            Ok(resp) => MpvResult::err(resp.error),
-            Err(e) => MpvResult::err(e),
-        }
+    pub fn load(&self, url: &str) -> Result<(), MpvError> {
+        let resp = self.ipc.send_command(&["loadfile", url])?;
+        self.check_response(resp)
     }
 
     /// Start playback
-    pub fn play(&self) -> MpvResult {
-        match self.ipc.set_property("pause", "no") {
-            Ok(_) => MpvResult::ok(),
-            Err(e) => MpvResult::err(e),
-        }
+    pub fn play(&self) -> Result<(), MpvError> {
+        self.ipc.set_property("pause", "no")
     }
 
     /// Pause playback
-    pub fn pause(&self) -> MpvResult {
-        match self.ipc.set_property("pause", "yes") {
-            Ok(_) => MpvResult::ok(),
-            Err(e) => MpvResult::err(e),
-        }
+    pub fn pause(&self) -> Result<(), MpvError> {
+        self.ipc.set_property("pause", "yes")
     }
 
     /// Toggle pause state
-    pub fn toggle_pause(&self) -> MpvResult {
-        match self.ipc.send_command(&["cycle", "pause"]) {
-            Ok(resp) if resp.error == "success" => MpvResult::ok(),
-            Ok(resp) => MpvResult::err(resp.error),
-            Err(e) => MpvResult::err(e),
-        }
+    pub fn toggle_pause(&self) -> Result<(), MpvError> {
+        let resp = self.ipc.send_command(&["cycle", "pause"])?;
+        self.check_response(resp)
     }
 
     /// Stop playback
-    pub fn stop(&self) -> MpvResult {
-        match self.ipc.send_command(&["stop"]) {
-            Ok(resp) if resp.error == "success" => MpvResult::ok(),
-            Ok(resp) => MpvResult::err(resp.error),
-            Err(e) => MpvResult::err(e),
-        }
+    pub fn stop(&self) -> Result<(), MpvError> {
+        let resp = self.ipc.send_command(&["stop"])?;
+        self.check_response(resp)
     }
 
     /// Set volume (0-100)
-    pub fn set_volume(&self, volume: f64) -> MpvResult {
-        match self.ipc.set_property("volume", &volume.to_string()) {
-            Ok(_) => MpvResult::ok(),
-            Err(e) => MpvResult::err(e),
-        }
+    pub fn set_volume(&self, volume: f64) -> Result<(), MpvError> {
+        self.ipc.set_property("volume", &volume.to_string())
     }
 
     /// Toggle mute
-    pub fn toggle_mute(&self) -> MpvResult {
-        match self.ipc.send_command(&["cycle", "mute"]) {
-            Ok(resp) if resp.error == "success" => MpvResult::ok(),
-            Ok(resp) => MpvResult::err(resp.error),
-            Err(e) => MpvResult::err(e),
-        }
+    pub fn toggle_mute(&self) -> Result<(), MpvError> {
+        let resp = self.ipc.send_command(&["cycle", "mute"])?;
+        self.check_response(resp)
     }
 
     /// Seek to position (seconds)
-    pub fn seek(&self, seconds: f64) -> MpvResult {
-        match self.ipc.send_command(&["seek", &seconds.to_string(), "absolute"]) {
-            Ok(resp) if resp.error == "success" => MpvResult::ok(),
-            Ok(resp) => MpvResult::err(resp.error),
-            Err(e) => MpvResult::err(e),
+    pub fn seek(&self, seconds: f64) -> Result<(), MpvError> {
+        let resp = self.ipc.send_command(&["seek", &seconds.to_string(), "absolute"])?;
+        self.check_response(resp)
+    }
+
+    /// Append a file to the playlist without interrupting current playback
+    pub fn playlist_append(&self, url: &str) -> Result<(), MpvError> {
+        let resp = self.ipc.send_command(&["loadfile", url, "append"])?;
+        self.check_response(resp)
+    }
+
+    /// Advance to the next playlist entry
+    pub fn playlist_next(&self) -> Result<(), MpvError> {
+        let resp = self.ipc.send_command(&["playlist-next"])?;
+        self.check_response(resp)
+    }
+
+    /// Go back to the previous playlist entry
+    pub fn playlist_prev(&self) -> Result<(), MpvError> {
+        let resp = self.ipc.send_command(&["playlist-prev"])?;
+        self.check_response(resp)
+    }
+
+    /// Clear the playlist except for the currently-playing entry
+    pub fn playlist_clear(&self) -> Result<(), MpvError> {
+        let resp = self.ipc.send_command(&["playlist-clear"])?;
+        self.check_response(resp)
+    }
+
+    /// Remove the entry at `index` from the playlist
+    pub fn playlist_remove(&self, index: usize) -> Result<(), MpvError> {
+        let resp = self.ipc.send_command(&["playlist-remove", &index.to_string()])?;
+        self.check_response(resp)
+    }
+
+    /// Turn an `MpvResponse` into `Ok(())`/`Err(MpvError::CommandFailed)`
+    fn check_response(&self, resp: super::ipc::MpvResponse) -> Result<(), MpvError> {
+        if resp.error == "success" {
+            Ok(())
+        } else {
+            Err(MpvError::CommandFailed { code: resp.error, message: "mpv command failed".to_string() })
         }
     }
 
-    /// Get current status
-    pub fn get_status(&self) -> MpvStatus {
-        let playing = self.ipc.get_property("pause")
-            .ok()
-            .flatten()
-            .and_then(|v| v.as_bool())
-            .map(|paused| !paused)
-            .unwrap_or(false);
-
-        let volume = self.ipc.get_property("volume")
-            .ok()
-            .flatten()
-            .and_then(|v| v.as_f64())
-            .unwrap_or(100.0);
-
-        let muted = self.ipc.get_property("mute")
-            .ok()
-            .flatten()
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
-
-        let position = self.ipc.get_property("time-pos")
-            .ok()
-            .flatten()
-            .and_then(|v| v.as_f64())
-            .unwrap_or(0.0);
-
-        let duration = self.ipc.get_property("duration")
-            .ok()
-            .flatten()
-            .and_then(|v| v.as_f64())
-            .unwrap_or(0.0);
-
-        MpvStatus {
-            playing,
-            volume,
-            muted,
-            position,
-            duration,
+    /// Read the current playlist
+    pub fn get_playlist(&self) -> Result<Vec<PlaylistEntry>, MpvError> {
+        match self.ipc.get_property("playlist")? {
+            Some(value) => Ok(serde_json::from_value(value)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Read the audio/sub/video tracks available for the current file.
+    pub fn get_tracks(&self) -> Result<Vec<TrackInfo>, MpvError> {
+        match self.ipc.get_property("track-list")? {
+            Some(value) => Ok(serde_json::from_value(value)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Select an audio track by id, or `None` to disable audio.
+    pub fn set_audio_track(&self, id: Option<i64>) -> Result<(), MpvError> {
+        self.ipc.set_property("aid", &track_id_value(id))
+    }
+
+    /// Select a subtitle track by id, or `None` to turn subtitles off.
+    pub fn set_subtitle_track(&self, id: Option<i64>) -> Result<(), MpvError> {
+        self.ipc.set_property("sid", &track_id_value(id))
+    }
+
+    /// Select a video track by id, or `None` to disable video.
+    pub fn set_video_track(&self, id: Option<i64>) -> Result<(), MpvError> {
+        self.ipc.set_property("vid", &track_id_value(id))
+    }
+
+    /// Load an external subtitle file or URL and add it to the track list.
+    pub fn add_subtitle(&self, path_or_url: &str) -> Result<(), MpvError> {
+        let resp = self.ipc.send_command(&["sub-add", path_or_url])?;
+        self.check_response(resp)
+    }
+
+    /// List the resolution variants available for the current adaptive
+    /// stream (HLS/DASH), one per demuxed video track.
+    pub fn get_qualities(&self) -> Result<Vec<QualityInfo>, MpvError> {
+        match self.ipc.get_property("track-list")? {
+            Some(value) => {
+                let tracks: Vec<RawTrackNode> = serde_json::from_value(value)?;
+                Ok(tracks
+                    .into_iter()
+                    .filter(|t| t.track_type == "video")
+                    .map(|t| QualityInfo {
+                        id: t.id,
+                        height: t.demux_h,
+                        width: t.demux_w,
+                        bitrate: t.demux_bitrate,
+                    })
+                    .collect())
+            }
+            None => Ok(Vec::new()),
         }
     }
+
+    /// Cap playback at `max_height` by switching to the highest-resolution
+    /// video track at or under that height, falling back to the lowest
+    /// available variant if every one exceeds it.
+    pub fn set_quality(&self, max_height: i64) -> Result<(), MpvError> {
+        let qualities = self.get_qualities()?;
+        let chosen = qualities
+            .iter()
+            .filter(|q| q.height.is_some_and(|h| h <= max_height))
+            .max_by_key(|q| q.height.unwrap_or(0))
+            .or_else(|| qualities.iter().min_by_key(|q| q.height.unwrap_or(i64::MAX)));
+
+        match chosen {
+            Some(q) => self.set_video_track(Some(q.id)),
+            None => Err(MpvError::PropertyUnavailable("track-list".to_string())),
+        }
+    }
+
+    /// Clone of this instance's shutdown flag, so a long-lived background
+    /// task outside `ExternalMpv` itself (the remote-control status
+    /// stream) can stop promptly when this instance is torn down instead
+    /// of continuing to poll a dead mpv process.
+    pub(super) fn shutdown_flag(&self) -> Arc<AtomicBool> {
+        self.shutdown.clone()
+    }
+
+    /// Get current status. Most callers should prefer listening for the
+    /// pushed `mpv-status` event instead of polling this.
+    pub fn get_status(&self) -> MpvStatus {
+        status_snapshot(&self.ipc)
+    }
 }
 
 impl Drop for ExternalMpv {
     fn drop(&mut self) {
         log::info!("[MPV-EXT] Shutting down...");
         self.shutdown.store(true, Ordering::SeqCst);
+
+        // The remote-control server drives this mpv session directly, so
+        // leaving it up after this session dies would have it pointing at
+        // a dead player - tear it down alongside.
+        if let Some(remote) = self.app.try_state::<Arc<super::remote_control::RemoteControlState>>() {
+            remote.stop();
+        }
+
         let _ = self.ipc.send_command_async(&["quit"]);
         let _ = self.process.kill();
 
@@ -392,6 +570,31 @@ impl Drop for ExternalMpv {
     }
 }
 
+/// mpv's `aid`/`sid`/`vid` properties take either a track id or the
+/// literal string `"no"` to disable that track entirely.
+fn track_id_value(id: Option<i64>) -> String {
+    match id {
+        Some(id) => id.to_string(),
+        None => "no".to_string(),
+    }
+}
+
+/// Subset of a `track-list` entry's fields needed for quality selection.
+/// mpv reports every demuxed variant of an adaptive stream as its own
+/// video track, annotated with `demux-w`/`demux-h`/`demux-bitrate`.
+#[derive(Deserialize)]
+struct RawTrackNode {
+    id: i64,
+    #[serde(rename = "type")]
+    track_type: String,
+    #[serde(rename = "demux-w")]
+    demux_w: Option<i64>,
+    #[serde(rename = "demux-h")]
+    demux_h: Option<i64>,
+    #[serde(rename = "demux-bitrate")]
+    demux_bitrate: Option<i64>,
+}
+
 /// Get HWND from Tauri window (Windows only)
 #[cfg(target_os = "windows")]
 fn get_hwnd(window: &tauri::WebviewWindow) -> Result<isize, String> {
@@ -406,24 +609,86 @@ fn get_hwnd(window: &tauri::WebviewWindow) -> Result<isize, String> {
     }
 }
 
-/// Handle mpv property change events
-fn handle_mpv_event(app: &AppHandle, event: MpvEvent) {
+/// Push the current `status_cache` snapshot to the frontend as a single
+/// `mpv-status` event, replacing the old pattern of telling the frontend
+/// to go re-poll `mpv_get_status` on every change.
+fn emit_status(app: &AppHandle, status_cache: &Mutex<MpvStatus>) {
+    let status = status_cache.lock().unwrap().clone();
+    let _ = app.emit("mpv-status", status);
+}
+
+/// Handle mpv property change events: update the cached `MpvStatus` and
+/// push it to the frontend, or, for properties that aren't part of
+/// `MpvStatus`, emit a dedicated event (`mpv-eof`/`mpv-idle`/
+/// `playlist-changed`).
+fn handle_mpv_event(app: &AppHandle, event: MpvEvent, status_cache: &Mutex<MpvStatus>) {
     if event.event != "property-change" {
         return;
     }
 
-    // Build status from event data
-    // Note: We emit individual events for now, could batch into status updates
-    if let Some(name) = &event.name {
-        match name.as_str() {
-            "pause" | "volume" | "mute" | "time-pos" | "duration" => {
-                // Emit generic status update - frontend should request full status
-                // This is simpler than tracking state in the backend
-                let _ = app.emit("mpv-property-change", ());
+    let Some(name) = &event.name else {
+        return;
+    };
+
+    match name.as_str() {
+        "pause" => {
+            if let Some(paused) = event.data.as_ref().and_then(|v| v.as_bool()) {
+                status_cache.lock().unwrap().playing = !paused;
+            }
+            emit_status(app, status_cache);
+        }
+        "volume" => {
+            if let Some(volume) = event.data.as_ref().and_then(|v| v.as_f64()) {
+                status_cache.lock().unwrap().volume = volume;
+            }
+            emit_status(app, status_cache);
+        }
+        "mute" => {
+            if let Some(muted) = event.data.as_ref().and_then(|v| v.as_bool()) {
+                status_cache.lock().unwrap().muted = muted;
+            }
+            emit_status(app, status_cache);
+        }
+        "time-pos" => {
+            if let Some(position) = event.data.as_ref().and_then(|v| v.as_f64()) {
+                status_cache.lock().unwrap().position = position;
             }
-            _ => {}
+            emit_status(app, status_cache);
         }
+        "duration" => {
+            if let Some(duration) = event.data.as_ref().and_then(|v| v.as_f64()) {
+                status_cache.lock().unwrap().duration = duration;
+            }
+            emit_status(app, status_cache);
+        }
+        "playlist-pos" => {
+            if let Some(pos) = event.data.as_ref().and_then(|v| v.as_i64()) {
+                status_cache.lock().unwrap().playlist_pos = pos;
+            }
+            emit_status(app, status_cache);
+        }
+        "playlist-count" => {
+            if let Some(count) = event.data.as_ref().and_then(|v| v.as_u64()) {
+                status_cache.lock().unwrap().playlist_count = count as usize;
+            }
+            emit_status(app, status_cache);
+        }
+        "playlist" => {
+            let _ = app.emit("playlist-changed", ());
+        }
+        "eof-reached" => {
+            let at_eof = event.data.as_ref().and_then(|v| v.as_bool()).unwrap_or(false);
+            let _ = app.emit("mpv-eof", at_eof);
+        }
+        "core-idle" => {
+            let idle = event.data.as_ref().and_then(|v| v.as_bool()).unwrap_or(false);
+            let _ = app.emit("mpv-idle", idle);
+        }
+        _ => {}
     }
+
+    // Relay pause/time-pos changes to any active watch-party peers.
+    sync_party::on_local_property_change(app, name, event.data.as_ref());
 }
 
 /// State holder for external mpv (managed by Tauri)
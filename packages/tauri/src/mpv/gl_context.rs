@@ -5,6 +5,9 @@ use surfman::{
 };
 use euclid::default::Size2D;
 
+#[cfg(target_os = "linux")]
+use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
+
 /// Headless OpenGL context for offscreen rendering.
 /// Uses Surfman for cross-platform support (Linux/Windows/macOS).
 pub struct HeadlessGLContext {
@@ -13,6 +16,66 @@ pub struct HeadlessGLContext {
     // Surface is bound to context - context owns it while bound
 }
 
+/// Build a surfman `Connection` from the Tauri main window's own display
+/// handle (Wayland/X11) instead of `Connection::new()`'s isolated one, so
+/// `HeadlessGLContext::from_connection` can put the render context on the
+/// same display as the window that will eventually present it. Falls back
+/// to `Connection::new()` at the call site if this fails (e.g. a backend
+/// without raw-window-handle support) - see `mod::init_mpv_fbo`.
+#[cfg(target_os = "linux")]
+pub fn connection_from_window(window: &tauri::WebviewWindow) -> Result<Connection, String> {
+    let handle = window
+        .display_handle()
+        .map_err(|e| format!("Failed to get window display handle: {}", e))?;
+
+    // Safety: `handle` borrows from `window`, which outlives this call, and
+    // the resulting `Connection` only reads from the display - it doesn't
+    // outlive the window either (both are torn down together on shutdown).
+    unsafe { Connection::from_raw_display_handle(handle.as_raw()) }
+        .map_err(|e| format!("Surfman connection from window display failed: {:?}", e))
+}
+
+/// Build a surfman `NativeWidget` for the Tauri main window from `connection`
+/// (which must itself have come from that window - see
+/// `connection_from_window`), so `HeadlessGLContext::from_connection` can
+/// bind the render context's surface directly to the window instead of a
+/// throwaway offscreen one.
+///
+/// That binding alone doesn't get a frame on screen yet: presenting through
+/// it would mean blitting `render_thread_fbo`'s offscreen FBO into this
+/// surface and calling `present_context_surface` every frame, but by the
+/// time rendering starts `libmpv2::render::RenderContext` already owns the
+/// `HeadlessGLContext` outright (it's moved in as the OpenGL callback
+/// context), so there's no handle left outside it to blit or present
+/// through. Today this only avoids the meaningless 1x1 throwaway surface in
+/// favor of a real window-sized one - it is not a present path, and makes
+/// no difference to how frames currently reach the screen (still the
+/// `OffscreenRenderer::copy_into` shm-ring / dmabuf paths in
+/// `render_thread_fbo`). Wiring an actual present requires restructuring
+/// that ownership first (e.g. giving `render_thread_fbo` back a shared
+/// handle to the device/context instead of moving `HeadlessGLContext` away
+/// whole).
+#[cfg(target_os = "linux")]
+pub fn native_widget_from_window(
+    connection: &Connection,
+    window: &tauri::WebviewWindow,
+) -> Result<surfman::NativeWidget, String> {
+    let handle = window
+        .window_handle()
+        .map_err(|e| format!("Failed to get window handle: {}", e))?;
+    let size = window
+        .inner_size()
+        .map_err(|e| format!("Failed to get window size: {}", e))?;
+
+    // Safety: `handle` borrows from `window`, which outlives this call and
+    // the resulting `NativeWidget` - both are torn down together on
+    // shutdown, same as `connection_from_window`'s display handle.
+    unsafe {
+        connection.create_native_widget_from_rwh(handle.as_raw(), Size2D::new(size.width as i32, size.height as i32))
+    }
+    .map_err(|e| format!("Surfman native widget from window failed: {:?}", e))
+}
+
 impl HeadlessGLContext {
     pub fn new() -> Result<Self, String> {
         log::info!("[VIDEO] Creating surfman connection...");
@@ -22,6 +85,19 @@ impl HeadlessGLContext {
             .map_err(|e| format!("Surfman connection failed: {:?}", e))?;
         log::info!("[VIDEO] Surfman connection created");
 
+        Self::from_connection(connection, None)
+    }
+
+    /// Same as `new`, but reuses an existing display `Connection` - e.g.
+    /// one obtained via `connection_from_window` - instead of opening an
+    /// isolated one, and optionally binds the context's surface directly
+    /// to the window via `native_widget` (see `native_widget_from_window`)
+    /// instead of a throwaway offscreen one.
+    ///
+    /// See the doc comment on `native_widget_from_window` for why that
+    /// binding doesn't currently change how frames reach the screen: it's
+    /// real window-surface plumbing, not a present path.
+    pub fn from_connection(connection: Connection, native_widget: Option<surfman::NativeWidget>) -> Result<Self, String> {
         // Select GPU
         log::info!("[VIDEO] Creating adapter...");
         let adapter = connection.create_adapter()
@@ -61,12 +137,20 @@ impl HeadlessGLContext {
         // On Linux/X11, we need a surface for the context to work properly.
         #[cfg(target_os = "linux")]
         {
-            log::info!("[VIDEO] Creating surface (Linux requires it)...");
-            let surface = device.create_surface(
-                &context,
-                SurfaceAccess::GPUOnly,
-                SurfaceType::Generic { size: Size2D::new(1, 1) },
-            ).map_err(|e| format!("Surfman surface creation failed: {:?}", e))?;
+            let surface = match native_widget {
+                Some(widget) => {
+                    log::info!("[VIDEO] Creating surface bound to the window...");
+                    device
+                        .create_surface(&context, SurfaceAccess::GPUOnly, SurfaceType::Widget { native_widget: widget })
+                        .map_err(|e| format!("Surfman widget surface creation failed: {:?}", e))?
+                }
+                None => {
+                    log::info!("[VIDEO] No window handle available, creating a throwaway 1x1 surface...");
+                    device
+                        .create_surface(&context, SurfaceAccess::GPUOnly, SurfaceType::Generic { size: Size2D::new(1, 1) })
+                        .map_err(|e| format!("Surfman surface creation failed: {:?}", e))?
+                }
+            };
             log::info!("[VIDEO] Surface created");
 
             log::info!("[VIDEO] Binding surface to context...");
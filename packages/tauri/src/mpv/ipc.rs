@@ -1,22 +1,170 @@
-//! IPC client for communicating with external mpv process.
+//! Async IPC client for mpv's JSON IPC socket, built on Tokio pipes.
 //!
-//! Windows: Named pipes (\\.\pipe\mpv-socket-{pid})
-//! Linux: Unix sockets (/tmp/mpv-socket-{pid})
-
+//! Windows: named pipe (\\.\pipe\mpv-socket-{pid})
+//! Unix: Unix domain socket (/tmp/mpv-socket-{pid})
+//!
+//! A single supervisor task (spawned in `connect`) owns the connection for
+//! its whole lifetime: it demultiplexes mpv's JSON-lines stream into
+//! command replies - matched back to callers by `request_id` through a
+//! `HashMap<u64, oneshot::Sender<_>>` - and `event`/`property-change`
+//! notifications, forwarded on a broadcast channel. Because that loop is
+//! cooperatively scheduled instead of blocking a dedicated OS thread,
+//! Windows no longer needs to skip it: a blocking reader thread there used
+//! to hang the whole app because it shared a handle with the UI thread.
+//! Callers outside the async runtime (the synchronous `ExternalMpv` API) go
+//! through the `block_on` wrappers below, so their signatures don't change.
+//!
+//! Writes go through the same supervisor loop, fed by an unbounded mpsc
+//! queue of already-serialized lines: `send_command` enqueues and returns
+//! immediately rather than holding a socket lock across the write, so many
+//! requests can be pipelined in flight at once instead of serializing
+//! behind one mutex. Each request's slot in `pending` is owned by a
+//! `PendingGuard` that evicts it on drop - on a normal response, on
+//! timeout, or if the caller's future is cancelled outright - so a stalled
+//! mpv can no longer grow `pending` without bound.
+//!
+//! If mpv exits or the socket drops, the supervisor notices (EOF on the
+//! read side, or a failed write) and takes over: every currently-pending
+//! request is immediately failed with `MpvError::Disconnected` instead of
+//! waiting out its full timeout, `connection_state()` moves to
+//! `Reconnecting`, and the supervisor retries the connection with
+//! exponential backoff. Once reconnected, every property still tracked in
+//! `subscribed_properties` gets re-observed so a caller's `Subscription`
+//! keeps working transparently across an mpv restart. `write_tx` and the
+//! `pending`/`subscriptions` maps are never recreated, so none of this is
+//! visible to callers beyond the errors a request in flight at the wrong
+//! moment may see.
+
+use super::error::MpvError;
+use futures_util::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::io::{BufRead, BufReader, Write};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
+use tokio_util::codec::{Framed, LinesCodec};
 
 #[cfg(target_os = "windows")]
-use std::fs::OpenOptions;
-#[cfg(target_os = "windows")]
-use std::os::windows::fs::OpenOptionsExt;
+use tokio::net::windows::named_pipe::{ClientOptions, NamedPipeClient};
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use tokio::net::UnixStream;
 
+#[cfg(target_os = "windows")]
+type PipeStream = NamedPipeClient;
 #[cfg(any(target_os = "linux", target_os = "macos"))]
-use std::os::unix::net::UnixStream;
+type PipeStream = UnixStream;
+
+type WriteHalf = futures_util::stream::SplitSink<Framed<PipeStream, LinesCodec>, String>;
+
+/// How long `send_command` waits for a reply before giving up, unless the
+/// caller picks a different timeout via `send_command_with_timeout`.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Backoff between reconnect attempts, doubling up to `MAX_RECONNECT_BACKOFF`.
+const INITIAL_RECONNECT_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(10);
+/// Consecutive failed reconnect attempts before the supervisor gives up and
+/// settles into `ConnectionState::Failed` for good.
+const MAX_RECONNECT_ATTEMPTS: u32 = 10;
+
+/// Lifecycle of the underlying socket/pipe connection, observable via
+/// `MpvIpcClient::connection_state` or `watch_connection_state` so the UI
+/// can show "reconnecting..." instead of individual commands just timing
+/// out one by one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connecting,
+    Connected,
+    Reconnecting,
+    /// The supervisor exhausted `MAX_RECONNECT_ATTEMPTS` and stopped
+    /// retrying. Terminal - a new `MpvIpcClient` must be created.
+    Failed,
+}
+
+type PendingMap = Arc<Mutex<HashMap<u64, oneshot::Sender<Result<MpvResponse, MpvError>>>>>;
+
+/// Owns a request's slot in `pending` for as long as a reply is still
+/// awaited. Evicts the slot on drop - whether that's because the reply
+/// arrived (a no-op; the supervisor already removed it), the wait timed
+/// out, or the future was dropped before either happened - which is what
+/// keeps `pending` bounded under mpv stalls instead of leaking an entry
+/// per timed-out command.
+struct PendingGuard {
+    pending: PendingMap,
+    request_id: u64,
+    rx: oneshot::Receiver<Result<MpvResponse, MpvError>>,
+}
+
+impl PendingGuard {
+    fn register(pending: PendingMap, request_id: u64) -> Self {
+        let (tx, rx) = oneshot::channel();
+        pending.lock().unwrap().insert(request_id, tx);
+        Self { pending, request_id, rx }
+    }
+
+    async fn wait(self, timeout: Duration) -> Result<MpvResponse, MpvError> {
+        match tokio::time::timeout(timeout, self.rx).await {
+            Ok(Ok(result)) => result,
+            // Sender dropped without sending - the supervisor only does
+            // that while tearing the connection down, so treat it the
+            // same as an explicit disconnect notification.
+            Ok(Err(_)) => Err(MpvError::Disconnected),
+            Err(_) => Err(MpvError::Timeout),
+        }
+    }
+}
+
+impl Drop for PendingGuard {
+    fn drop(&mut self) {
+        self.pending.lock().unwrap().remove(&self.request_id);
+    }
+}
+
+/// First observe id auto-allocated by `subscribe`. Kept well above the
+/// hand-assigned ids `ExternalMpv` uses for its own `observe_property`
+/// calls (1-10) so the two schemes can't collide if ever used on the same
+/// connection.
+const FIRST_AUTO_OBSERVE_ID: u64 = 1000;
+
+type SubscriptionCallback = Box<dyn Fn(Value) + Send + Sync>;
+type SubscriptionMap = Arc<Mutex<HashMap<u64, SubscriptionCallback>>>;
+/// Property name behind each observe id in `SubscriptionMap`, kept
+/// separately because callbacks aren't cloneable/inspectable - the
+/// supervisor needs the name, not the callback, to reissue
+/// `observe_property` after a reconnect.
+type SubscribedProperties = Arc<Mutex<HashMap<u64, String>>>;
+
+/// RAII handle for one `subscribe` registration. Dropping it sends
+/// `unobserve_property` for this id and removes the callback, so a caller
+/// that stops caring about a property doesn't have to remember to tear
+/// the subscription down by hand.
+pub struct Subscription {
+    observe_id: u64,
+    subscriptions: SubscriptionMap,
+    subscribed_properties: SubscribedProperties,
+    write_tx: mpsc::UnboundedSender<String>,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.subscriptions.lock().unwrap().remove(&self.observe_id);
+        self.subscribed_properties.lock().unwrap().remove(&self.observe_id);
+
+        // Fire-and-forget, like `send_command_async` - nothing is waiting
+        // on a `pending` entry for this one, so a bare request id is fine.
+        let cmd = MpvCommand {
+            command: vec![Value::String("unobserve_property".to_string()), Value::from(self.observe_id)],
+            request_id: 0,
+        };
+        if let Ok(json) = serde_json::to_string(&cmd) {
+            let _ = self.write_tx.send(json);
+        }
+    }
+}
 
 /// JSON-IPC message sent to mpv
 #[derive(Serialize)]
@@ -26,22 +174,15 @@ struct MpvCommand {
 }
 
 /// JSON-IPC response from mpv
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct MpvResponse {
     pub error: String,
     pub data: Option<Value>,
     pub request_id: Option<u64>,
 }
 
-/// Property change event from mpv
-#[derive(Deserialize, Debug, Clone)]
-pub struct MpvPropertyChange {
-    pub name: String,
-    pub data: Option<Value>,
-}
-
 /// Event from mpv
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct MpvEvent {
     pub event: String,
     pub name: Option<String>,
@@ -49,184 +190,371 @@ pub struct MpvEvent {
     pub id: Option<u64>,
 }
 
-/// Thread-safe IPC client for mpv
+/// Async IPC client for mpv. The supervisor task it spawns in `connect`
+/// runs for the lifetime of the connection, reconnecting underneath as
+/// needed; sync methods below block on the Tauri-managed Tokio runtime so
+/// `ExternalMpv`'s synchronous API doesn't have to change shape.
 pub struct MpvIpcClient {
     request_id: AtomicU64,
-    #[cfg(target_os = "windows")]
-    writer: Arc<Mutex<std::fs::File>>,
-    #[cfg(any(target_os = "linux", target_os = "macos"))]
-    writer: Arc<Mutex<UnixStream>>,
-    pending: Arc<Mutex<HashMap<u64, std::sync::mpsc::Sender<MpvResponse>>>>,
+    /// Serialized lines queued for the supervisor's writer half (see
+    /// `connect_async`); sending here never blocks on socket I/O.
+    write_tx: mpsc::UnboundedSender<String>,
+    pending: PendingMap,
+    events: broadcast::Sender<MpvEvent>,
+    /// Allocates ids for `subscribe`, independent of `request_id`.
+    next_observe_id: AtomicU64,
+    /// Typed callbacks registered via `subscribe`, keyed by observe id;
+    /// the supervisor dispatches into these directly instead of
+    /// forwarding every `property-change` event onto `events` for
+    /// callers to string-match themselves.
+    subscriptions: SubscriptionMap,
+    subscribed_properties: SubscribedProperties,
+    state_rx: watch::Receiver<ConnectionState>,
 }
 
 impl MpvIpcClient {
-    /// Connect to mpv's IPC socket
-    pub fn connect(socket_path: &str) -> Result<Self, String> {
+    /// Connect to mpv's IPC socket and spawn the supervisor task. Only
+    /// this first connection attempt is synchronous - once it succeeds,
+    /// any later drop is handled by the supervisor's own reconnect loop.
+    pub fn connect(socket_path: &str) -> Result<Self, MpvError> {
+        tauri::async_runtime::block_on(Self::connect_async(socket_path))
+    }
+
+    async fn connect_async(socket_path: &str) -> Result<Self, MpvError> {
         log::info!("[MPV-IPC] Connecting to {}", socket_path);
+        let stream = Self::open_stream(socket_path).await?;
+        log::info!("[MPV-IPC] Connected successfully");
+
+        let pending: PendingMap = Arc::new(Mutex::new(HashMap::new()));
+        let subscriptions: SubscriptionMap = Arc::new(Mutex::new(HashMap::new()));
+        let subscribed_properties: SubscribedProperties = Arc::new(Mutex::new(HashMap::new()));
+        let (events_tx, _) = broadcast::channel(64);
+        let (write_tx, write_rx) = mpsc::unbounded_channel();
+        let (state_tx, state_rx) = watch::channel(ConnectionState::Connected);
+
+        tauri::async_runtime::spawn(Self::supervisor_task(
+            socket_path.to_string(),
+            Some(stream),
+            write_rx,
+            pending.clone(),
+            subscriptions.clone(),
+            subscribed_properties.clone(),
+            events_tx.clone(),
+            state_tx,
+        ));
 
+        Ok(Self {
+            request_id: AtomicU64::new(1),
+            write_tx,
+            pending,
+            events: events_tx,
+            next_observe_id: AtomicU64::new(FIRST_AUTO_OBSERVE_ID),
+            subscriptions,
+            subscribed_properties,
+            state_rx,
+        })
+    }
+
+    async fn open_stream(socket_path: &str) -> Result<PipeStream, MpvError> {
         #[cfg(target_os = "windows")]
-        let stream = {
-            // Windows named pipe - need to open with specific flags
-            OpenOptions::new()
-                .read(true)
-                .write(true)
-                .custom_flags(0) // Default flags work for named pipes
+        {
+            ClientOptions::new()
                 .open(socket_path)
-                .map_err(|e| format!("Failed to connect to mpv pipe: {}", e))?
-        };
+                .map_err(|e| MpvError::ConnectFailed(format!("Failed to connect to mpv pipe: {}", e)))
+        }
 
         #[cfg(any(target_os = "linux", target_os = "macos"))]
-        let stream = UnixStream::connect(socket_path)
-            .map_err(|e| format!("Failed to connect to mpv socket: {}", e))?;
+        {
+            UnixStream::connect(socket_path)
+                .await
+                .map_err(|e| MpvError::ConnectFailed(format!("Failed to connect to mpv socket: {}", e)))
+        }
+    }
 
-        log::info!("[MPV-IPC] Connected successfully");
+    /// Owns the connection end to end for the client's whole lifetime:
+    /// connects (or reconnects with backoff), drains `write_rx` onto the
+    /// socket, and dispatches incoming lines - until the socket drops, at
+    /// which point it fails every pending request and loops back to
+    /// reconnect. `initial_stream` lets the already-open connection from
+    /// `connect_async` be reused instead of reconnecting immediately.
+    #[allow(clippy::too_many_arguments)]
+    async fn supervisor_task(
+        socket_path: String,
+        mut initial_stream: Option<PipeStream>,
+        mut write_rx: mpsc::UnboundedReceiver<String>,
+        pending: PendingMap,
+        subscriptions: SubscriptionMap,
+        subscribed_properties: SubscribedProperties,
+        events_tx: broadcast::Sender<MpvEvent>,
+        state_tx: watch::Sender<ConnectionState>,
+    ) {
+        let mut backoff = INITIAL_RECONNECT_BACKOFF;
+        let mut attempt: u32 = 0;
+
+        loop {
+            let stream = match initial_stream.take() {
+                Some(stream) => stream,
+                None => {
+                    let _ = state_tx.send(ConnectionState::Reconnecting);
+                    match Self::open_stream(&socket_path).await {
+                        Ok(stream) => stream,
+                        Err(e) => {
+                            attempt += 1;
+                            log::error!("[MPV-IPC] Reconnect attempt {} failed: {}", attempt, e);
+                            if attempt >= MAX_RECONNECT_ATTEMPTS {
+                                log::error!("[MPV-IPC] Giving up after {} attempts", attempt);
+                                let _ = state_tx.send(ConnectionState::Failed);
+                                Self::fail_all_pending(&pending);
+                                return;
+                            }
+                            tokio::time::sleep(backoff).await;
+                            backoff = (backoff * 2).min(MAX_RECONNECT_BACKOFF);
+                            continue;
+                        }
+                    }
+                }
+            };
 
-        Ok(Self {
-            request_id: AtomicU64::new(1),
-            writer: Arc::new(Mutex::new(stream)),
-            pending: Arc::new(Mutex::new(HashMap::new())),
-        })
-    }
+            attempt = 0;
+            backoff = INITIAL_RECONNECT_BACKOFF;
+            let _ = state_tx.send(ConnectionState::Connected);
+            log::info!("[MPV-IPC] Connection (re)established");
+
+            let (mut writer, mut reader) = Framed::new(stream, LinesCodec::new()).split();
+            Self::replay_subscriptions(&subscribed_properties, &mut writer).await;
+
+            loop {
+                tokio::select! {
+                    line = write_rx.recv() => {
+                        match line {
+                            Some(line) => {
+                                if let Err(e) = writer.send(line).await {
+                                    log::error!("[MPV-IPC] Write error: {}", e);
+                                    break;
+                                }
+                            }
+                            // Every sender (the client plus every live
+                            // `Subscription`) dropped - nothing left to
+                            // serve, so the supervisor can retire too.
+                            None => {
+                                log::info!("[MPV-IPC] Supervisor exiting, no senders remain");
+                                return;
+                            }
+                        }
+                    }
+                    incoming = reader.next() => {
+                        match incoming {
+                            Some(Ok(line)) => {
+                                Self::dispatch_line(&line, &pending, &subscriptions, &events_tx);
+                            }
+                            Some(Err(e)) => {
+                                log::error!("[MPV-IPC] Read error: {}", e);
+                                break;
+                            }
+                            None => {
+                                log::warn!("[MPV-IPC] mpv closed the connection");
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
 
-    /// Send a command to mpv and wait for response
-    pub fn send_command(&self, command: &[&str]) -> Result<MpvResponse, String> {
-        let request_id = self.request_id.fetch_add(1, Ordering::SeqCst);
+            Self::fail_all_pending(&pending);
+        }
+    }
 
-        let cmd = MpvCommand {
-            command: command.iter().map(|s| Value::String(s.to_string())).collect(),
-            request_id,
-        };
+    /// Re-issue `observe_property` for every property a `Subscription` is
+    /// still alive for, so callers don't have to notice a reconnect
+    /// happened at all. Written directly to `writer` (rather than via
+    /// `write_tx`) so it happens before the fresh connection's main
+    /// select loop starts taking other queued writes.
+    async fn replay_subscriptions(subscribed_properties: &SubscribedProperties, writer: &mut WriteHalf) {
+        let properties: Vec<(u64, String)> =
+            subscribed_properties.lock().unwrap().iter().map(|(id, property)| (*id, property.clone())).collect();
+
+        for (id, property) in properties {
+            let cmd = MpvCommand {
+                command: vec![
+                    Value::String("observe_property".to_string()),
+                    Value::from(id),
+                    Value::String(property.clone()),
+                ],
+                request_id: 0,
+            };
+            match serde_json::to_string(&cmd) {
+                Ok(json) => {
+                    if let Err(e) = writer.send(json).await {
+                        log::error!("[MPV-IPC] Failed to replay subscription for '{}': {}", property, e);
+                    }
+                }
+                Err(e) => log::error!("[MPV-IPC] Failed to serialize replayed subscription: {}", e),
+            }
+        }
+    }
 
-        let json = serde_json::to_string(&cmd)
-            .map_err(|e| format!("Failed to serialize command: {}", e))?;
+    /// Parse one incoming line as either an event or a command response
+    /// and route it accordingly. Shared between the first connection and
+    /// every reconnect the supervisor makes.
+    fn dispatch_line(line: &str, pending: &PendingMap, subscriptions: &SubscriptionMap, events_tx: &broadcast::Sender<MpvEvent>) {
+        if line.is_empty() {
+            return;
+        }
 
-        // Create response channel
-        let (tx, rx) = std::sync::mpsc::channel();
-        {
-            let mut pending = self.pending.lock().unwrap();
-            pending.insert(request_id, tx);
+        // Try parsing as event first
+        if let Ok(event) = serde_json::from_str::<MpvEvent>(line) {
+            if event.event == "property-change" {
+                if let (Some(id), Some(data)) = (event.id, event.data.clone()) {
+                    if let Some(callback) = subscriptions.lock().unwrap().get(&id) {
+                        callback(data);
+                    }
+                }
+                let _ = events_tx.send(event);
+                return;
+            }
+        }
+        // Try parsing as response
+        if let Ok(response) = serde_json::from_str::<MpvResponse>(line) {
+            if let Some(request_id) = response.request_id {
+                if let Some(tx) = pending.lock().unwrap().remove(&request_id) {
+                    let _ = tx.send(Ok(response));
+                }
+            }
         }
+    }
 
-        // Send command
-        {
-            let mut writer = self.writer.lock().unwrap();
-            writeln!(writer, "{}", json)
-                .map_err(|e| format!("Failed to send command: {}", e))?;
-            writer.flush()
-                .map_err(|e| format!("Failed to flush: {}", e))?;
+    /// Immediately fail every request still waiting on a reply, instead of
+    /// making callers wait out their full timeout to discover the
+    /// connection is gone.
+    fn fail_all_pending(pending: &PendingMap) {
+        for (_, tx) in pending.lock().unwrap().drain() {
+            let _ = tx.send(Err(MpvError::Disconnected));
         }
+    }
 
-        // Wait for response (with timeout)
-        rx.recv_timeout(std::time::Duration::from_secs(5))
-            .map_err(|_| "Command timeout".to_string())
+    /// Current connection lifecycle state. See `ConnectionState`.
+    pub fn connection_state(&self) -> ConnectionState {
+        *self.state_rx.borrow()
     }
 
-    /// Send a command without waiting for response
-    pub fn send_command_async(&self, command: &[&str]) -> Result<(), String> {
-        let request_id = self.request_id.fetch_add(1, Ordering::SeqCst);
+    /// A `watch::Receiver` for observing connection state changes as they
+    /// happen, for callers that want to react to reconnects rather than
+    /// just poll `connection_state`.
+    pub fn watch_connection_state(&self) -> watch::Receiver<ConnectionState> {
+        self.state_rx.clone()
+    }
+
+    /// Subscribe to every raw property-change event on this connection.
+    /// Callers that only care about one property and want it typed should
+    /// use `subscribe` instead.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<MpvEvent> {
+        self.events.subscribe()
+    }
 
+    /// Serialize and enqueue a command for the supervisor's writer half.
+    /// Returns as soon as it's queued - it does not wait for the line to
+    /// actually reach the socket, let alone for a reply.
+    fn write_command(&self, command: &[&str], request_id: u64) -> Result<(), MpvError> {
         let cmd = MpvCommand {
             command: command.iter().map(|s| Value::String(s.to_string())).collect(),
             request_id,
         };
+        let json = serde_json::to_string(&cmd)?;
+        self.write_tx.send(json).map_err(|_| MpvError::Disconnected)
+    }
+
+    async fn send_command_async_inner(&self, command: &[&str], timeout: Duration) -> Result<MpvResponse, MpvError> {
+        let request_id = self.request_id.fetch_add(1, Ordering::SeqCst);
+        let guard = PendingGuard::register(self.pending.clone(), request_id);
 
-        let json = serde_json::to_string(&cmd)
-            .map_err(|e| format!("Failed to serialize command: {}", e))?;
+        self.write_command(command, request_id)?;
 
-        let mut writer = self.writer.lock().unwrap();
-        writeln!(writer, "{}", json)
-            .map_err(|e| format!("Failed to send command: {}", e))?;
-        writer.flush()
-            .map_err(|e| format!("Failed to flush: {}", e))?;
+        guard.wait(timeout).await
+    }
+
+    /// Send a command to mpv and wait for response, using `DEFAULT_TIMEOUT`.
+    pub fn send_command(&self, command: &[&str]) -> Result<MpvResponse, MpvError> {
+        self.send_command_with_timeout(command, DEFAULT_TIMEOUT)
+    }
+
+    /// Send a command to mpv and wait up to `timeout` for a response,
+    /// for callers that need something other than `DEFAULT_TIMEOUT`
+    /// (e.g. a longer allowance for a command known to be slow).
+    pub fn send_command_with_timeout(&self, command: &[&str], timeout: Duration) -> Result<MpvResponse, MpvError> {
+        tauri::async_runtime::block_on(self.send_command_async_inner(command, timeout))
+    }
 
-        Ok(())
+    /// Send a command without waiting for response
+    pub fn send_command_async(&self, command: &[&str]) -> Result<(), MpvError> {
+        let request_id = self.request_id.fetch_add(1, Ordering::SeqCst);
+        self.write_command(command, request_id)
     }
 
-    /// Observe a property for changes
-    pub fn observe_property(&self, id: u64, property: &str) -> Result<(), String> {
+    /// Observe a property for changes. Records `id`/`property` into
+    /// `subscribed_properties` regardless of caller - both `subscribe`'s
+    /// auto-allocated ids and `ExternalMpv`'s hand-assigned ones (see
+    /// `FIRST_AUTO_OBSERVE_ID`) - so `replay_subscriptions` re-issues it
+    /// after a reconnect. Callers that want a typed callback dispatched
+    /// for changes should use `subscribe` instead; this is for
+    /// `ExternalMpv`, which reads values back through `get_property`/the
+    /// event stream rather than a `subscribe` callback.
+    pub fn observe_property(&self, id: u64, property: &str) -> Result<(), MpvError> {
+        self.subscribed_properties.lock().unwrap().insert(id, property.to_string());
         self.send_command_async(&["observe_property", &id.to_string(), property])
     }
 
+    /// Register a typed callback for `property`'s changes: allocates an
+    /// observe id, issues `observe_property`, and dispatches future
+    /// `property-change` events for that id into `callback` deserialized
+    /// as `T` - no string-matching `MpvEvent.name` or hand-parsing `Value`
+    /// required. The subscription survives a reconnect: the supervisor
+    /// re-issues `observe_property` for it once a new connection is up.
+    /// Drop the returned `Subscription` to stop listening.
+    pub fn subscribe<T, F>(&self, property: &str, callback: F) -> Result<Subscription, MpvError>
+    where
+        T: DeserializeOwned,
+        F: Fn(T) + Send + Sync + 'static,
+    {
+        let observe_id = self.next_observe_id.fetch_add(1, Ordering::SeqCst);
+
+        let wrapped: SubscriptionCallback = Box::new(move |value: Value| {
+            if let Ok(typed) = serde_json::from_value::<T>(value) {
+                callback(typed);
+            }
+        });
+        self.subscriptions.lock().unwrap().insert(observe_id, wrapped);
+
+        if let Err(e) = self.observe_property(observe_id, property) {
+            self.subscriptions.lock().unwrap().remove(&observe_id);
+            self.subscribed_properties.lock().unwrap().remove(&observe_id);
+            return Err(e);
+        }
+
+        Ok(Subscription {
+            observe_id,
+            subscriptions: self.subscriptions.clone(),
+            subscribed_properties: self.subscribed_properties.clone(),
+            write_tx: self.write_tx.clone(),
+        })
+    }
+
     /// Get a property value
-    pub fn get_property(&self, property: &str) -> Result<Option<Value>, String> {
+    pub fn get_property(&self, property: &str) -> Result<Option<Value>, MpvError> {
         let response = self.send_command(&["get_property", property])?;
         if response.error == "success" {
             Ok(response.data)
         } else {
-            Err(response.error)
+            Err(MpvError::PropertyUnavailable(property.to_string()))
         }
     }
 
     /// Set a property value
-    pub fn set_property(&self, property: &str, value: &str) -> Result<(), String> {
+    pub fn set_property(&self, property: &str, value: &str) -> Result<(), MpvError> {
         let response = self.send_command(&["set_property", property, value])?;
         if response.error == "success" {
             Ok(())
         } else {
-            Err(response.error)
+            Err(MpvError::CommandFailed { code: response.error, message: format!("Failed to set {}", property) })
         }
     }
-
-    /// Handle an incoming response (called from reader thread)
-    pub fn handle_response(&self, response: MpvResponse) {
-        if let Some(request_id) = response.request_id {
-            let mut pending = self.pending.lock().unwrap();
-            if let Some(tx) = pending.remove(&request_id) {
-                let _ = tx.send(response);
-            }
-        }
-    }
-}
-
-/// Start a reader thread that processes mpv messages
-pub fn start_reader_thread<F>(
-    socket_path: &str,
-    ipc: Arc<MpvIpcClient>,
-    mut on_event: F,
-) -> Result<std::thread::JoinHandle<()>, String>
-where
-    F: FnMut(MpvEvent) + Send + 'static,
-{
-    #[cfg(target_os = "windows")]
-    let stream = OpenOptions::new()
-        .read(true)
-        .open(socket_path)
-        .map_err(|e| format!("Failed to open mpv pipe for reading: {}", e))?;
-
-    #[cfg(any(target_os = "linux", target_os = "macos"))]
-    let stream = UnixStream::connect(socket_path)
-        .map_err(|e| format!("Failed to connect to mpv socket for reading: {}", e))?;
-
-    let handle = std::thread::spawn(move || {
-        let reader = BufReader::new(stream);
-
-        for line in reader.lines() {
-            let line = match line {
-                Ok(l) => l,
-                Err(e) => {
-                    log::error!("[MPV-IPC] Read error: {}", e);
-                    break;
-                }
-            };
-
-            if line.is_empty() {
-                continue;
-            }
-
-            // Try parsing as event first
-            if let Ok(event) = serde_json::from_str::<MpvEvent>(&line) {
-                if event.event == "property-change" {
-                    on_event(event);
-                }
-            }
-            // Try parsing as response
-            else if let Ok(response) = serde_json::from_str::<MpvResponse>(&line) {
-                ipc.handle_response(response);
-            }
-        }
-
-        log::info!("[MPV-IPC] Reader thread exiting");
-    });
-
-    Ok(handle)
 }
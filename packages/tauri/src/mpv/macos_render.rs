@@ -0,0 +1,353 @@
+//! Native mpv playback on macOS via libmpv's render API.
+//!
+//! mpv's `--wid` embedding (what Windows/Linux use, see `external.rs`)
+//! isn't supported on macOS, and driving playback from the frontend's
+//! `<video>` tag can't handle everything desktop mpv can (odd codecs,
+//! subtitle rendering, custom filters). Instead we render into a
+//! `CAOpenGLLayer` attached as a sublayer of the webview's own view.
+//!
+//! Layer-backing the host view (`setWantsLayer: true`) *before* attaching
+//! anything is what avoids the classic non-layer-backed AppKit GL
+//! problems: an early flush dropping a frame, the system chrome
+//! flickering through during a live resize, and double-buffering that
+//! silently breaks once the window is layer-backed by something else
+//! anyway (which, on a modern macOS WKWebView-hosting window, it always
+//! ends up being).
+
+use super::{MpvStatus, PlaylistEntry, QualityInfo, TrackInfo};
+use libmpv2::render::{OpenGLInitParams, RenderContext, RenderParam, RenderParamApiType};
+use libmpv2::Mpv;
+use objc2::rc::Retained;
+use objc2::{msg_send, AnyThread};
+use objc2_app_kit::NSView;
+use objc2_quartz_core::CAOpenGLLayer;
+use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+use std::ffi::{c_void, CString};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+extern "C" {
+    fn CGLSetCurrentContext(ctx: *mut c_void) -> i32;
+    fn CGLFlushDrawable(ctx: *mut c_void) -> i32;
+}
+
+/// Get the webview's backing `NSView*` - the macOS analogue of
+/// `external::get_hwnd` on Windows.
+fn get_ns_view(window: &tauri::WebviewWindow) -> Result<*mut c_void, String> {
+    let handle = window
+        .window_handle()
+        .map_err(|e| format!("Failed to get window handle: {}", e))?;
+
+    match handle.as_raw() {
+        RawWindowHandle::AppKit(appkit) => Ok(appkit.ns_view.as_ptr()),
+        _ => Err("Not an AppKit window".to_string()),
+    }
+}
+
+/// Owns the `CAOpenGLLayer` attached to the webview's view and the CGL
+/// context it vends. Rendering happens off the main thread; `CGLContext`
+/// is safe to use that way as long as only one thread has it current at a
+/// time, which the dedicated render thread guarantees.
+struct LayerContext {
+    _layer: Retained<CAOpenGLLayer>,
+    cgl_context: *mut c_void,
+}
+
+unsafe impl Send for LayerContext {}
+
+impl LayerContext {
+    fn attach(ns_view: *mut c_void, width: u32, height: u32) -> Result<Self, String> {
+        unsafe {
+            let view: &NSView = &*(ns_view as *const NSView);
+            let _: () = msg_send![view, setWantsLayer: true];
+
+            let host_layer = view.layer().ok_or_else(|| "Webview view has no backing layer".to_string())?;
+
+            let layer = CAOpenGLLayer::new();
+            layer.setAsynchronous(false);
+            layer.setFrame(host_layer.bounds());
+            host_layer.addSublayer(&layer);
+
+            let pixel_format = layer.copyCGLPixelFormatForDisplayMask(0);
+            let cgl_context = layer.copyCGLContextForPixelFormat(&pixel_format);
+
+            let _ = width;
+            let _ = height;
+
+            Ok(Self { _layer: layer, cgl_context: cgl_context.cast() })
+        }
+    }
+
+    fn make_current(&self) {
+        unsafe {
+            CGLSetCurrentContext(self.cgl_context);
+        }
+    }
+
+    fn flush(&self) {
+        unsafe {
+            CGLFlushDrawable(self.cgl_context);
+        }
+    }
+
+    fn get_proc_address(&self, name: &str) -> *mut c_void {
+        extern "C" {
+            fn dlsym(handle: *mut c_void, symbol: *const i8) -> *mut c_void;
+        }
+        const RTLD_DEFAULT: *mut c_void = -2isize as *mut c_void;
+        let c_name = CString::new(name).unwrap();
+        unsafe { dlsym(RTLD_DEFAULT, c_name.as_ptr()) }
+    }
+}
+
+fn get_proc_address_cb(ctx: &LayerContext, name: &str) -> *mut c_void {
+    ctx.get_proc_address(name)
+}
+
+/// mpv's `aid`/`sid`/`vid` properties take either a track id or the
+/// literal string `"no"` to disable that track entirely.
+fn track_id_value(id: Option<i64>) -> String {
+    match id {
+        Some(id) => id.to_string(),
+        None => "no".to_string(),
+    }
+}
+
+/// Native macOS mpv instance, rendering into a layer-backed surface
+/// instead of going through an external process.
+pub struct MacosMpv {
+    mpv: Arc<Mpv>,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl MacosMpv {
+    /// Create the layer-backed render surface, start libmpv with the
+    /// OpenGL render API bound to it, and spawn the dedicated render
+    /// thread. Emits `mpv-ready` only once the layer is attached and the
+    /// first render pass has been wired up.
+    pub fn new(window: &tauri::WebviewWindow, app: AppHandle) -> Result<Self, String> {
+        let ns_view = get_ns_view(window)?;
+        let size = window.inner_size().map_err(|e| format!("Failed to get window size: {}", e))?;
+
+        let mpv = Mpv::with_initializer(|init| {
+            init.set_property("vo", "libmpv")?;
+            init.set_property("no-osc", true)?;
+            init.set_property("osd-level", 0i64)?;
+            init.set_property("keep-open", "yes")?;
+            init.set_property("idle", "yes")?;
+            init.set_property("input-default-bindings", "no")?;
+            init.set_property("hwdec", "auto")?;
+            init.set_property("tone-mapping", "mobius")?;
+            Ok(())
+        })
+        .map_err(|e| format!("Failed to create mpv: {}", e))?;
+
+        let mpv = Arc::new(mpv);
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let mpv_ptr = Arc::as_ptr(&mpv) as *mut Mpv;
+        let layer_ctx = LayerContext::attach(ns_view, size.width, size.height)?;
+        layer_ctx.make_current();
+
+        let render_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            RenderContext::new(
+                (*mpv_ptr).ctx.as_mut(),
+                vec![
+                    RenderParam::ApiType(RenderParamApiType::OpenGl),
+                    RenderParam::InitParams(OpenGLInitParams {
+                        get_proc_address: get_proc_address_cb,
+                        ctx: layer_ctx,
+                    }),
+                ],
+            )
+        }));
+
+        let mut render_ctx = match render_result {
+            Ok(Ok(ctx)) => ctx,
+            Ok(Err(e)) => return Err(format!("Failed to create render context: {:?}", e)),
+            Err(_) => return Err("Render context creation panicked".to_string()),
+        };
+
+        let render_pending = Arc::new(AtomicBool::new(true));
+        let render_pending_cb = render_pending.clone();
+        render_ctx.set_update_callback(move || {
+            render_pending_cb.store(true, Ordering::SeqCst);
+        });
+
+        let thread_shutdown = shutdown.clone();
+        let width = size.width.max(1);
+        let height = size.height.max(1);
+        std::thread::spawn(move || {
+            log::info!("[MPV-MACOS] Render thread starting ({}x{})", width, height);
+
+            while !thread_shutdown.load(Ordering::SeqCst) {
+                if render_pending.swap(false, Ordering::SeqCst) {
+                    // Render straight into the CAOpenGLLayer's own
+                    // default framebuffer (fbo 0) rather than an
+                    // offscreen FBO - there's nowhere else for the
+                    // frame to need copying to.
+                    if render_ctx.render::<LayerContext>(0, width as i32, height as i32, true).is_ok() {
+                        if let Some(api) = render_ctx.get_api::<LayerContext>() {
+                            api.flush();
+                        }
+                    }
+                }
+                std::thread::sleep(Duration::from_millis(8));
+            }
+
+            log::info!("[MPV-MACOS] Render thread exiting");
+        });
+
+        let _ = app.emit("mpv-ready", true);
+
+        Ok(Self { mpv, shutdown })
+    }
+
+    fn command(&self, args: &[&str]) -> Result<(), String> {
+        self.mpv.command(args[0], &args[1..]).map_err(|e| e.to_string())
+    }
+
+    pub fn load(&self, url: &str) -> Result<(), String> {
+        self.command(&["loadfile", url])
+    }
+
+    pub fn play(&self) -> Result<(), String> {
+        self.mpv.set_property("pause", false).map_err(|e| e.to_string())
+    }
+
+    pub fn pause(&self) -> Result<(), String> {
+        self.mpv.set_property("pause", true).map_err(|e| e.to_string())
+    }
+
+    pub fn toggle_pause(&self) -> Result<(), String> {
+        self.command(&["cycle", "pause"])
+    }
+
+    pub fn stop(&self) -> Result<(), String> {
+        self.command(&["stop"])
+    }
+
+    pub fn set_volume(&self, volume: f64) -> Result<(), String> {
+        self.mpv.set_property("volume", volume).map_err(|e| e.to_string())
+    }
+
+    pub fn toggle_mute(&self) -> Result<(), String> {
+        self.command(&["cycle", "mute"])
+    }
+
+    pub fn seek(&self, seconds: f64) -> Result<(), String> {
+        self.command(&["seek", &seconds.to_string(), "absolute"])
+    }
+
+    pub fn playlist_append(&self, url: &str) -> Result<(), String> {
+        self.command(&["loadfile", url, "append"])
+    }
+
+    pub fn playlist_next(&self) -> Result<(), String> {
+        self.command(&["playlist-next"])
+    }
+
+    pub fn playlist_prev(&self) -> Result<(), String> {
+        self.command(&["playlist-prev"])
+    }
+
+    pub fn playlist_clear(&self) -> Result<(), String> {
+        self.command(&["playlist-clear"])
+    }
+
+    pub fn playlist_remove(&self, index: usize) -> Result<(), String> {
+        self.command(&["playlist-remove", &index.to_string()])
+    }
+
+    /// Select an audio track by id, or `None` to disable audio. Goes
+    /// through the `set` command rather than `set_property` so `"no"`
+    /// (disable) and a numeric track id can share one code path despite
+    /// not being the same libmpv property type.
+    pub fn set_audio_track(&self, id: Option<i64>) -> Result<(), String> {
+        self.command(&["set", "aid", &track_id_value(id)])
+    }
+
+    /// Select a subtitle track by id, or `None` to turn subtitles off.
+    pub fn set_subtitle_track(&self, id: Option<i64>) -> Result<(), String> {
+        self.command(&["set", "sid", &track_id_value(id)])
+    }
+
+    /// Select a video track by id, or `None` to disable video.
+    pub fn set_video_track(&self, id: Option<i64>) -> Result<(), String> {
+        self.command(&["set", "vid", &track_id_value(id)])
+    }
+
+    /// Load an external subtitle file or URL and add it to the track list.
+    pub fn add_subtitle(&self, path_or_url: &str) -> Result<(), String> {
+        self.command(&["sub-add", path_or_url])
+    }
+
+    /// Tracks aren't exposed through libmpv2's typed `get_property` (see
+    /// the note on `get_playlist`); the frontend can't pick an audio/sub
+    /// track on macOS until that gap is closed.
+    pub fn get_tracks(&self) -> Vec<TrackInfo> {
+        Vec::new()
+    }
+
+    /// Same `track-list` node-decoding gap as `get_tracks` - quality
+    /// variants can't be enumerated on macOS until that's closed.
+    pub fn get_qualities(&self) -> Vec<QualityInfo> {
+        Vec::new()
+    }
+
+    /// With no quality list to choose from (see `get_qualities`), there's
+    /// nothing to switch to.
+    pub fn set_quality(&self, _max_height: i64) -> Result<(), String> {
+        Err("Quality selection not available on this platform".to_string())
+    }
+
+    pub fn get_playlist(&self) -> Vec<PlaylistEntry> {
+        // `playlist` is an mpv node (not a plain scalar), which libmpv2's
+        // typed `get_property` doesn't decode for us the way the JSON-IPC
+        // path does for `ExternalMpv`; the frontend already tracks its
+        // own queue for playback it's driving, so leave this empty for
+        // now rather than hand-rolling node decoding here.
+        Vec::new()
+    }
+
+    pub fn get_status(&self) -> MpvStatus {
+        let playing = self.mpv.get_property::<bool>("pause").map(|paused| !paused).unwrap_or(false);
+        let volume = self.mpv.get_property::<f64>("volume").unwrap_or(100.0);
+        let muted = self.mpv.get_property::<bool>("mute").unwrap_or(false);
+        let position = self.mpv.get_property::<f64>("time-pos").unwrap_or(0.0);
+        let duration = self.mpv.get_property::<f64>("duration").unwrap_or(0.0);
+        let playlist_pos = self.mpv.get_property::<i64>("playlist-pos").unwrap_or(-1);
+        let playlist_count = self.mpv.get_property::<i64>("playlist-count").unwrap_or(0) as usize;
+
+        MpvStatus {
+            playing,
+            volume,
+            muted,
+            position,
+            duration,
+            playlist_pos,
+            playlist_count,
+        }
+    }
+}
+
+impl Drop for MacosMpv {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+    }
+}
+
+/// State holder for native macOS mpv (managed by Tauri), mirroring
+/// `ExternalMpvState`'s shape so the `mpv_*` command bodies can look the
+/// same across all three platforms.
+pub struct MacosMpvState {
+    pub mpv: Option<MacosMpv>,
+}
+
+impl MacosMpvState {
+    pub fn new() -> Self {
+        Self { mpv: None }
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Platform rendering strategies:
 //! - Windows: External mpv process with --wid (renders to HWND)
-//! - macOS: Native <video> tag (mpv GL broken, frontend handles)
+//! - macOS: Native mpv via libmpv's render API into a layer-backed surface
 //! - Linux: Native <video> tag by default, optional external mpv window
 //!
 //! FBO fallback (feature-gated) for debugging or if native doesn't work.
@@ -12,14 +12,48 @@
 pub mod external;
 #[cfg(any(target_os = "windows", target_os = "linux"))]
 pub mod ipc;
+// Typed errors for the external mpv/IPC path
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub mod error;
+// Watch-party sync, layered on top of the external mpv IPC client
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub mod sync_party;
+// Remote control server (phone/second machine), layered on top of the
+// external mpv IPC client
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub mod remote_control;
+// Seek-bar hover thumbnails, rendered by a dedicated mpv instance
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub mod thumbnail;
+
+// Native mpv playback (macOS), via libmpv's render API into a
+// layer-backed surface rather than the `--wid` embedding mpv doesn't
+// support on this platform
+#[cfg(target_os = "macos")]
+pub mod macos_render;
 
 // FBO-based rendering (fallback, feature-gated)
 #[cfg(feature = "fbo-fallback")]
 pub mod gl_context;
 #[cfg(feature = "fbo-fallback")]
 pub mod renderer;
-
-use serde::Serialize;
+// GPU RGBA->YUV420 color conversion, layered on top of the FBO fallback.
+// Falls back to the CPU loop in `renderer::read_as_yuv420` on software-GL.
+#[cfg(all(feature = "fbo-fallback", feature = "gpu-yuv"))]
+pub mod yuv_gpu;
+// Zero-copy DMABUF export of the FBO render target, layered on top of the
+// FBO fallback. Linux-only (GBM/DRI EGL); falls back to the
+// `FrameRingBuffer` CPU-copy path in `render_thread_fbo` when unavailable.
+#[cfg(all(feature = "fbo-fallback", feature = "dmabuf-export", target_os = "linux"))]
+pub mod dmabuf;
+// Optional PipeWire virtual-camera output, fed from the same frames the
+// FBO fallback renders. Linux-only.
+#[cfg(all(feature = "fbo-fallback", feature = "pipewire-screencast", target_os = "linux"))]
+pub mod screencast;
+#[cfg(all(feature = "fbo-fallback", feature = "pipewire-screencast", target_os = "linux"))]
+pub use screencast::{mpv_start_screencast, mpv_stop_screencast, ScreencastState};
+
+use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 use tauri::{AppHandle, Emitter, Manager, State};
 
@@ -41,12 +75,62 @@ pub struct MpvStatus {
     pub muted: bool,
     pub position: f64,
     pub duration: f64,
+    pub playlist_pos: i64,
+    pub playlist_count: usize,
+}
+
+/// One entry in mpv's playlist, as reported by the `playlist` property.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct PlaylistEntry {
+    pub filename: String,
+    pub title: Option<String>,
+    #[serde(default)]
+    pub current: bool,
+    #[serde(default)]
+    pub playing: bool,
+}
+
+/// One entry in mpv's `track-list` property (audio/sub/video tracks for
+/// the currently loaded file).
+#[derive(Clone, Deserialize, Serialize)]
+pub struct TrackInfo {
+    pub id: i64,
+    #[serde(rename = "type")]
+    pub track_type: String,
+    pub lang: Option<String>,
+    pub title: Option<String>,
+    #[serde(default)]
+    pub default: bool,
+    #[serde(default)]
+    pub selected: bool,
+}
+
+/// One selectable resolution variant for an adaptive (HLS/DASH) stream.
+/// Manifest variants show up in mpv's `track-list` as separate demuxed
+/// video tracks once the stream is loaded, so this mirrors `TrackInfo`
+/// but surfaces the resolution/bitrate fields callers need to pick one.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct QualityInfo {
+    pub id: i64,
+    pub height: Option<i64>,
+    pub width: Option<i64>,
+    pub bitrate: Option<i64>,
+}
+
+/// Structured error payload sent to the frontend. `kind` is a stable,
+/// machine-readable tag (e.g. `"binary_not_found"`) the UI can switch on
+/// to decide whether to show a retry button or a "install mpv" prompt;
+/// `message` is the human-readable detail for logging/display.
+#[derive(Clone, Serialize)]
+pub struct MpvErrorPayload {
+    pub kind: &'static str,
+    pub message: String,
 }
 
 #[derive(Clone, Serialize)]
 pub struct MpvResult {
     pub success: Option<bool>,
-    pub error: Option<String>,
+    pub error: Option<MpvErrorPayload>,
 }
 
 impl MpvResult {
@@ -56,10 +140,12 @@ impl MpvResult {
             error: None,
         }
     }
+    /// Generic error constructor for ad hoc messages (e.g. "mpv not
+    /// initialized") that don't originate from the typed `MpvError` path.
     pub fn err(msg: impl Into<String>) -> Self {
         Self {
             success: None,
-            error: Some(msg.into()),
+            error: Some(MpvErrorPayload { kind: "error", message: msg.into() }),
         }
     }
 }
@@ -74,11 +160,10 @@ pub struct MpvState {
     external: Mutex<ExternalMpvState>,
 }
 
-/// State for native video (macOS - mpv not used on backend)
+/// State for native mpv playback on macOS (see `macos_render`)
 #[cfg(target_os = "macos")]
 pub struct MpvState {
-    // macOS uses native <video> tag, no backend mpv needed
-    _phantom: std::marker::PhantomData<()>,
+    external: Mutex<macos_render::MacosMpvState>,
 }
 
 // ============================================================================
@@ -87,7 +172,7 @@ pub struct MpvState {
 
 /// Initialize mpv for the platform.
 /// Windows: Spawns external mpv with --wid embedding
-/// macOS: No-op (frontend uses native video)
+/// macOS: Spawns native mpv via libmpv's render API
 /// Linux: No-op by default (frontend uses native video), external mpv on demand
 #[cfg(target_os = "windows")]
 pub fn init_mpv(app: &AppHandle) -> Result<(), String> {
@@ -112,6 +197,9 @@ pub fn init_mpv(app: &AppHandle) -> Result<(), String> {
                 external: Mutex::new(ExternalMpvState { mpv: Some(mpv) }),
             };
             app.manage(state);
+            app.manage(std::sync::Arc::new(sync_party::SyncState::new()));
+            app.manage(std::sync::Arc::new(remote_control::RemoteControlState::new()));
+            app.manage(std::sync::Arc::new(thumbnail::ThumbnailState::new()));
             Ok(())
         }
         Err(e) => {
@@ -119,25 +207,44 @@ pub fn init_mpv(app: &AppHandle) -> Result<(), String> {
             app.manage(MpvState {
                 external: Mutex::new(ExternalMpvState::new()),
             });
-            Err(e)
+            app.manage(std::sync::Arc::new(sync_party::SyncState::new()));
+            app.manage(std::sync::Arc::new(remote_control::RemoteControlState::new()));
+            app.manage(std::sync::Arc::new(thumbnail::ThumbnailState::new()));
+            Err(e.to_string())
         }
     }
 }
 
 #[cfg(target_os = "macos")]
 pub fn init_mpv(app: &AppHandle) -> Result<(), String> {
-    log::info!("[MPV] macOS: Using native video playback (frontend <video> tag)");
+    log::info!("[MPV] macOS: Using native mpv via the libmpv render API");
 
-    // macOS doesn't need backend mpv - frontend handles video natively
-    let state = MpvState {
-        _phantom: std::marker::PhantomData,
+    let window = match app.get_webview_window("main") {
+        Some(w) => w,
+        None => {
+            log::error!("[MPV] Main window not found - registering empty state");
+            app.manage(MpvState {
+                external: Mutex::new(macos_render::MacosMpvState::new()),
+            });
+            return Err("Main window not found".to_string());
+        }
     };
-    app.manage(state);
 
-    // Emit ready immediately - frontend handles playback
-    let _ = app.emit("mpv-ready", true);
-
-    Ok(())
+    match macos_render::MacosMpv::new(&window, app.clone()) {
+        Ok(mpv) => {
+            app.manage(MpvState {
+                external: Mutex::new(macos_render::MacosMpvState { mpv: Some(mpv) }),
+            });
+            Ok(())
+        }
+        Err(e) => {
+            log::error!("[MPV] Failed to start native mpv: {} - registering empty state", e);
+            app.manage(MpvState {
+                external: Mutex::new(macos_render::MacosMpvState::new()),
+            });
+            Err(e)
+        }
+    }
 }
 
 #[cfg(target_os = "linux")]
@@ -149,6 +256,9 @@ pub fn init_mpv(app: &AppHandle) -> Result<(), String> {
         external: Mutex::new(ExternalMpvState::new()),
     };
     app.manage(state);
+    app.manage(std::sync::Arc::new(sync_party::SyncState::new()));
+    app.manage(std::sync::Arc::new(remote_control::RemoteControlState::new()));
+    app.manage(std::sync::Arc::new(thumbnail::ThumbnailState::new()));
 
     // Emit ready immediately - frontend handles playback by default
     let _ = app.emit("mpv-ready", true);
@@ -165,7 +275,7 @@ pub fn init_mpv(app: &AppHandle) -> Result<(), String> {
 pub fn mpv_load(url: String, state: State<MpvState>) -> MpvResult {
     let guard = state.external.lock().unwrap();
     match &guard.mpv {
-        Some(mpv) => mpv.load(&url),
+        Some(mpv) => mpv.load(&url).into(),
         None => MpvResult::err("mpv not initialized"),
     }
 }
@@ -175,7 +285,7 @@ pub fn mpv_load(url: String, state: State<MpvState>) -> MpvResult {
 pub fn mpv_play(state: State<MpvState>) -> MpvResult {
     let guard = state.external.lock().unwrap();
     match &guard.mpv {
-        Some(mpv) => mpv.play(),
+        Some(mpv) => mpv.play().into(),
         None => MpvResult::err("mpv not initialized"),
     }
 }
@@ -185,7 +295,7 @@ pub fn mpv_play(state: State<MpvState>) -> MpvResult {
 pub fn mpv_pause(state: State<MpvState>) -> MpvResult {
     let guard = state.external.lock().unwrap();
     match &guard.mpv {
-        Some(mpv) => mpv.pause(),
+        Some(mpv) => mpv.pause().into(),
         None => MpvResult::err("mpv not initialized"),
     }
 }
@@ -195,7 +305,7 @@ pub fn mpv_pause(state: State<MpvState>) -> MpvResult {
 pub fn mpv_toggle_pause(state: State<MpvState>) -> MpvResult {
     let guard = state.external.lock().unwrap();
     match &guard.mpv {
-        Some(mpv) => mpv.toggle_pause(),
+        Some(mpv) => mpv.toggle_pause().into(),
         None => MpvResult::err("mpv not initialized"),
     }
 }
@@ -205,7 +315,7 @@ pub fn mpv_toggle_pause(state: State<MpvState>) -> MpvResult {
 pub fn mpv_stop(state: State<MpvState>) -> MpvResult {
     let guard = state.external.lock().unwrap();
     match &guard.mpv {
-        Some(mpv) => mpv.stop(),
+        Some(mpv) => mpv.stop().into(),
         None => MpvResult::err("mpv not initialized"),
     }
 }
@@ -215,7 +325,7 @@ pub fn mpv_stop(state: State<MpvState>) -> MpvResult {
 pub fn mpv_set_volume(volume: f64, state: State<MpvState>) -> MpvResult {
     let guard = state.external.lock().unwrap();
     match &guard.mpv {
-        Some(mpv) => mpv.set_volume(volume),
+        Some(mpv) => mpv.set_volume(volume).into(),
         None => MpvResult::err("mpv not initialized"),
     }
 }
@@ -225,7 +335,7 @@ pub fn mpv_set_volume(volume: f64, state: State<MpvState>) -> MpvResult {
 pub fn mpv_toggle_mute(state: State<MpvState>) -> MpvResult {
     let guard = state.external.lock().unwrap();
     match &guard.mpv {
-        Some(mpv) => mpv.toggle_mute(),
+        Some(mpv) => mpv.toggle_mute().into(),
         None => MpvResult::err("mpv not initialized"),
     }
 }
@@ -235,7 +345,7 @@ pub fn mpv_toggle_mute(state: State<MpvState>) -> MpvResult {
 pub fn mpv_seek(seconds: f64, state: State<MpvState>) -> MpvResult {
     let guard = state.external.lock().unwrap();
     match &guard.mpv {
-        Some(mpv) => mpv.seek(seconds),
+        Some(mpv) => mpv.seek(seconds).into(),
         None => MpvResult::err("mpv not initialized"),
     }
 }
@@ -252,73 +362,382 @@ pub fn mpv_get_status(state: State<MpvState>) -> MpvStatus {
             muted: false,
             position: 0.0,
             duration: 0.0,
+            playlist_pos: -1,
+            playlist_count: 0,
         },
     }
 }
 
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn mpv_playlist_append(url: String, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.playlist_append(&url).into(),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn mpv_playlist_next(state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.playlist_next().into(),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn mpv_playlist_prev(state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.playlist_prev().into(),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn mpv_playlist_clear(state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.playlist_clear().into(),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn mpv_playlist_remove(index: usize, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.playlist_remove(index).into(),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn mpv_get_playlist(state: State<MpvState>) -> Result<Vec<PlaylistEntry>, MpvErrorPayload> {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.get_playlist().map_err(Into::into),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn mpv_get_tracks(state: State<MpvState>) -> Result<Vec<TrackInfo>, MpvErrorPayload> {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.get_tracks().map_err(Into::into),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn mpv_set_audio_track(id: Option<i64>, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.set_audio_track(id).into(),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn mpv_set_subtitle_track(id: Option<i64>, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.set_subtitle_track(id).into(),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn mpv_set_video_track(id: Option<i64>, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.set_video_track(id).into(),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn mpv_add_subtitle(path_or_url: String, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.add_subtitle(&path_or_url).into(),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn mpv_get_qualities(state: State<MpvState>) -> Result<Vec<QualityInfo>, MpvErrorPayload> {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.get_qualities().map_err(Into::into),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn mpv_set_quality(max_height: i64, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.set_quality(max_height).into(),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
 // ============================================================================
-// Tauri Commands - macOS (Native Video - No-op backend)
+// Tauri Commands - macOS (Native mpv via libmpv render API)
 // ============================================================================
 
+/// `MacosMpv`'s methods return a bare `Result<(), String>` (there's no
+/// typed `MpvError` on this path, since it doesn't go through `mpv/ipc`)
+/// - fold that into the `MpvResult` shape the frontend expects.
+#[cfg(target_os = "macos")]
+fn to_mpv_result(result: Result<(), String>) -> MpvResult {
+    match result {
+        Ok(()) => MpvResult::ok(),
+        Err(e) => MpvResult::err(e),
+    }
+}
+
 #[cfg(target_os = "macos")]
 #[tauri::command]
-pub fn mpv_load(_url: String, _state: State<MpvState>) -> MpvResult {
-    // macOS: Frontend handles video via native <video> tag
-    MpvResult::ok()
+pub fn mpv_load(url: String, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => to_mpv_result(mpv.load(&url)),
+        None => MpvResult::err("mpv not initialized"),
+    }
 }
 
 #[cfg(target_os = "macos")]
 #[tauri::command]
-pub fn mpv_play(_state: State<MpvState>) -> MpvResult {
-    MpvResult::ok()
+pub fn mpv_play(state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => to_mpv_result(mpv.play()),
+        None => MpvResult::err("mpv not initialized"),
+    }
 }
 
 #[cfg(target_os = "macos")]
 #[tauri::command]
-pub fn mpv_pause(_state: State<MpvState>) -> MpvResult {
-    MpvResult::ok()
+pub fn mpv_pause(state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => to_mpv_result(mpv.pause()),
+        None => MpvResult::err("mpv not initialized"),
+    }
 }
 
 #[cfg(target_os = "macos")]
 #[tauri::command]
-pub fn mpv_toggle_pause(_state: State<MpvState>) -> MpvResult {
-    MpvResult::ok()
+pub fn mpv_toggle_pause(state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => to_mpv_result(mpv.toggle_pause()),
+        None => MpvResult::err("mpv not initialized"),
+    }
 }
 
 #[cfg(target_os = "macos")]
 #[tauri::command]
-pub fn mpv_stop(_state: State<MpvState>) -> MpvResult {
-    MpvResult::ok()
+pub fn mpv_stop(state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => to_mpv_result(mpv.stop()),
+        None => MpvResult::err("mpv not initialized"),
+    }
 }
 
 #[cfg(target_os = "macos")]
 #[tauri::command]
-pub fn mpv_set_volume(_volume: f64, _state: State<MpvState>) -> MpvResult {
-    MpvResult::ok()
+pub fn mpv_set_volume(volume: f64, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => to_mpv_result(mpv.set_volume(volume)),
+        None => MpvResult::err("mpv not initialized"),
+    }
 }
 
 #[cfg(target_os = "macos")]
 #[tauri::command]
-pub fn mpv_toggle_mute(_state: State<MpvState>) -> MpvResult {
-    MpvResult::ok()
+pub fn mpv_toggle_mute(state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => to_mpv_result(mpv.toggle_mute()),
+        None => MpvResult::err("mpv not initialized"),
+    }
 }
 
 #[cfg(target_os = "macos")]
 #[tauri::command]
-pub fn mpv_seek(_seconds: f64, _state: State<MpvState>) -> MpvResult {
-    MpvResult::ok()
+pub fn mpv_seek(seconds: f64, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => to_mpv_result(mpv.seek(seconds)),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn mpv_get_status(state: State<MpvState>) -> MpvStatus {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.get_status(),
+        None => MpvStatus {
+            playing: false,
+            volume: 100.0,
+            muted: false,
+            position: 0.0,
+            duration: 0.0,
+            playlist_pos: -1,
+            playlist_count: 0,
+        },
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn mpv_playlist_append(url: String, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => to_mpv_result(mpv.playlist_append(&url)),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn mpv_playlist_next(state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => to_mpv_result(mpv.playlist_next()),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn mpv_playlist_prev(state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => to_mpv_result(mpv.playlist_prev()),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn mpv_playlist_clear(state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => to_mpv_result(mpv.playlist_clear()),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn mpv_playlist_remove(index: usize, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => to_mpv_result(mpv.playlist_remove(index)),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn mpv_get_playlist(state: State<MpvState>) -> Result<Vec<PlaylistEntry>, MpvErrorPayload> {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => Ok(mpv.get_playlist()),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn mpv_get_tracks(state: State<MpvState>) -> Result<Vec<TrackInfo>, MpvErrorPayload> {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => Ok(mpv.get_tracks()),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn mpv_set_audio_track(id: Option<i64>, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => to_mpv_result(mpv.set_audio_track(id)),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn mpv_set_subtitle_track(id: Option<i64>, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => to_mpv_result(mpv.set_subtitle_track(id)),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn mpv_set_video_track(id: Option<i64>, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => to_mpv_result(mpv.set_video_track(id)),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn mpv_add_subtitle(path_or_url: String, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => to_mpv_result(mpv.add_subtitle(&path_or_url)),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn mpv_get_qualities(state: State<MpvState>) -> Result<Vec<QualityInfo>, MpvErrorPayload> {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => Ok(mpv.get_qualities()),
+        None => Ok(Vec::new()),
+    }
 }
 
 #[cfg(target_os = "macos")]
 #[tauri::command]
-pub fn mpv_get_status(_state: State<MpvState>) -> MpvStatus {
-    // macOS: Frontend tracks status via HTML5 video events
-    MpvStatus {
-        playing: false,
-        volume: 100.0,
-        muted: false,
-        position: 0.0,
-        duration: 0.0,
+pub fn mpv_set_quality(max_height: i64, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => to_mpv_result(mpv.set_quality(max_height)),
+        None => MpvResult::err("mpv not initialized"),
     }
 }
 
@@ -331,7 +750,7 @@ pub fn mpv_get_status(_state: State<MpvState>) -> MpvStatus {
 pub fn mpv_load(url: String, state: State<MpvState>) -> MpvResult {
     let guard = state.external.lock().unwrap();
     match &guard.mpv {
-        Some(mpv) => mpv.load(&url),
+        Some(mpv) => mpv.load(&url).into(),
         None => {
             // No external mpv - frontend uses native video
             MpvResult::ok()
@@ -344,7 +763,7 @@ pub fn mpv_load(url: String, state: State<MpvState>) -> MpvResult {
 pub fn mpv_play(state: State<MpvState>) -> MpvResult {
     let guard = state.external.lock().unwrap();
     match &guard.mpv {
-        Some(mpv) => mpv.play(),
+        Some(mpv) => mpv.play().into(),
         None => MpvResult::ok(),
     }
 }
@@ -354,7 +773,7 @@ pub fn mpv_play(state: State<MpvState>) -> MpvResult {
 pub fn mpv_pause(state: State<MpvState>) -> MpvResult {
     let guard = state.external.lock().unwrap();
     match &guard.mpv {
-        Some(mpv) => mpv.pause(),
+        Some(mpv) => mpv.pause().into(),
         None => MpvResult::ok(),
     }
 }
@@ -364,7 +783,7 @@ pub fn mpv_pause(state: State<MpvState>) -> MpvResult {
 pub fn mpv_toggle_pause(state: State<MpvState>) -> MpvResult {
     let guard = state.external.lock().unwrap();
     match &guard.mpv {
-        Some(mpv) => mpv.toggle_pause(),
+        Some(mpv) => mpv.toggle_pause().into(),
         None => MpvResult::ok(),
     }
 }
@@ -374,7 +793,7 @@ pub fn mpv_toggle_pause(state: State<MpvState>) -> MpvResult {
 pub fn mpv_stop(state: State<MpvState>) -> MpvResult {
     let guard = state.external.lock().unwrap();
     match &guard.mpv {
-        Some(mpv) => mpv.stop(),
+        Some(mpv) => mpv.stop().into(),
         None => MpvResult::ok(),
     }
 }
@@ -384,7 +803,7 @@ pub fn mpv_stop(state: State<MpvState>) -> MpvResult {
 pub fn mpv_set_volume(volume: f64, state: State<MpvState>) -> MpvResult {
     let guard = state.external.lock().unwrap();
     match &guard.mpv {
-        Some(mpv) => mpv.set_volume(volume),
+        Some(mpv) => mpv.set_volume(volume).into(),
         None => MpvResult::ok(),
     }
 }
@@ -394,7 +813,7 @@ pub fn mpv_set_volume(volume: f64, state: State<MpvState>) -> MpvResult {
 pub fn mpv_toggle_mute(state: State<MpvState>) -> MpvResult {
     let guard = state.external.lock().unwrap();
     match &guard.mpv {
-        Some(mpv) => mpv.toggle_mute(),
+        Some(mpv) => mpv.toggle_mute().into(),
         None => MpvResult::ok(),
     }
 }
@@ -404,7 +823,7 @@ pub fn mpv_toggle_mute(state: State<MpvState>) -> MpvResult {
 pub fn mpv_seek(seconds: f64, state: State<MpvState>) -> MpvResult {
     let guard = state.external.lock().unwrap();
     match &guard.mpv {
-        Some(mpv) => mpv.seek(seconds),
+        Some(mpv) => mpv.seek(seconds).into(),
         None => MpvResult::ok(),
     }
 }
@@ -421,10 +840,142 @@ pub fn mpv_get_status(state: State<MpvState>) -> MpvStatus {
             muted: false,
             position: 0.0,
             duration: 0.0,
+            playlist_pos: -1,
+            playlist_count: 0,
         },
     }
 }
 
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub fn mpv_playlist_append(url: String, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.playlist_append(&url).into(),
+        None => MpvResult::ok(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub fn mpv_playlist_next(state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.playlist_next().into(),
+        None => MpvResult::ok(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub fn mpv_playlist_prev(state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.playlist_prev().into(),
+        None => MpvResult::ok(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub fn mpv_playlist_clear(state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.playlist_clear().into(),
+        None => MpvResult::ok(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub fn mpv_playlist_remove(index: usize, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.playlist_remove(index).into(),
+        None => MpvResult::ok(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub fn mpv_get_playlist(state: State<MpvState>) -> Result<Vec<PlaylistEntry>, MpvErrorPayload> {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.get_playlist().map_err(Into::into),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub fn mpv_get_tracks(state: State<MpvState>) -> Result<Vec<TrackInfo>, MpvErrorPayload> {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.get_tracks().map_err(Into::into),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub fn mpv_set_audio_track(id: Option<i64>, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.set_audio_track(id).into(),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub fn mpv_set_subtitle_track(id: Option<i64>, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.set_subtitle_track(id).into(),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub fn mpv_set_video_track(id: Option<i64>, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.set_video_track(id).into(),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub fn mpv_add_subtitle(path_or_url: String, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.add_subtitle(&path_or_url).into(),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub fn mpv_get_qualities(state: State<MpvState>) -> Result<Vec<QualityInfo>, MpvErrorPayload> {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.get_qualities().map_err(Into::into),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub fn mpv_set_quality(max_height: i64, state: State<MpvState>) -> MpvResult {
+    let guard = state.external.lock().unwrap();
+    match &guard.mpv {
+        Some(mpv) => mpv.set_quality(max_height).into(),
+        None => MpvResult::err("mpv not initialized"),
+    }
+}
+
 /// Enable external mpv window mode on Linux (power-user setting)
 #[cfg(target_os = "linux")]
 #[tauri::command]
@@ -441,7 +992,7 @@ pub fn mpv_enable_external_window(app: AppHandle, state: State<MpvState>) -> Mpv
             guard.mpv = Some(mpv);
             MpvResult::ok()
         }
-        Err(e) => MpvResult::err(e),
+        Err(e) => e.into(),
     }
 }
 
@@ -467,6 +1018,91 @@ pub fn mpv_disable_external_window(_state: State<MpvState>) -> MpvResult {
     MpvResult::err("External window mode only available on Linux")
 }
 
+// ============================================================================
+// Watch-party sync (Windows + Linux only, see sync_party)
+// ============================================================================
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub use sync_party::{mpv_sync_host, mpv_sync_join, mpv_sync_leave, mpv_sync_set_source};
+
+// Stub commands for macOS, which has no backend mpv to sync
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub async fn mpv_sync_host(
+    _username: String,
+    _port: u16,
+    _source: String,
+    _app: AppHandle,
+) -> Result<MpvResult, ()> {
+    Ok(MpvResult::err("Watch-party sync not available on this platform"))
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub async fn mpv_sync_join(_username: String, _host: String, _app: AppHandle) -> Result<MpvResult, ()> {
+    Ok(MpvResult::err("Watch-party sync not available on this platform"))
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn mpv_sync_set_source(_url: String) -> MpvResult {
+    MpvResult::err("Watch-party sync not available on this platform")
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn mpv_sync_leave() -> MpvResult {
+    MpvResult::err("Watch-party sync not available on this platform")
+}
+
+// ============================================================================
+// Remote control server (Windows + Linux only, see remote_control)
+// ============================================================================
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub use remote_control::{mpv_remote_auth_token, mpv_remote_start, mpv_remote_stop};
+
+// Stub commands for macOS, which has no backend mpv to control remotely
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub async fn mpv_remote_start(_port: u16, _app: AppHandle) -> Result<MpvResult, ()> {
+    Ok(MpvResult::err("Remote control not available on this platform"))
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn mpv_remote_stop() -> MpvResult {
+    MpvResult::err("Remote control not available on this platform")
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn mpv_remote_auth_token() -> Option<String> {
+    None
+}
+
+// ============================================================================
+// Seek-bar thumbnails (Windows + Linux only, see thumbnail)
+// ============================================================================
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub use thumbnail::mpv_thumbnail_at;
+
+// Stub command for macOS, which has no backend mpv to render thumbnails from
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub async fn mpv_thumbnail_at(
+    _url: String,
+    _time_secs: f64,
+    _max_w: u32,
+    _max_h: u32,
+) -> Result<String, MpvErrorPayload> {
+    Err(MpvErrorPayload {
+        kind: "error",
+        message: "Seek-bar thumbnails not available on this platform".to_string(),
+    })
+}
+
 // ============================================================================
 // FBO Fallback (feature-gated)
 // ============================================================================
@@ -482,21 +1118,114 @@ mod fbo_fallback {
     use super::*;
     use crate::mpv::gl_context::HeadlessGLContext;
     use crate::mpv::renderer::OffscreenRenderer;
+    #[cfg(feature = "fbo-jpeg-debug")]
     use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
     use libmpv2::render::{OpenGLInitParams, RenderContext, RenderParam, RenderParamApiType};
     use libmpv2::Mpv;
+    use shared_memory::{Shmem, ShmemConf};
     use std::ffi::c_void;
     use std::sync::atomic::{AtomicBool, Ordering};
     use std::sync::Arc;
     use std::time::{Duration, Instant};
     use tauri::Emitter;
 
-    /// Frame data sent to the frontend via Tauri events
+    /// Pixel layout of the bytes sitting in a frame's shared-memory
+    /// segment. Only one variant today - `copy_into` always writes
+    /// straight RGBA - but this keeps the wire format self-describing if a
+    /// packed YUV path is added later instead of a breaking payload change.
+    #[derive(Clone, Copy, Serialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum FramePixelFormat {
+        Rgba8,
+    }
+
+    /// Descriptor for a frame now sitting in the ring buffer, sent to the
+    /// frontend via the `mpv-frame` event. Carries no pixel bytes itself -
+    /// the frontend maps `os_id` once per `segment_id` it hasn't seen yet
+    /// and reads directly out of that mapping from then on.
     #[derive(Clone, Serialize)]
     pub struct FrameData {
+        pub segment_id: usize,
+        pub os_id: String,
         pub width: u32,
         pub height: u32,
-        pub jpeg: String,
+        pub stride: u32,
+        pub format: FramePixelFormat,
+    }
+
+    /// One pre-allocated shared-memory segment big enough to hold a frame.
+    struct FrameSegment {
+        shmem: Shmem,
+        os_id: String,
+    }
+
+    // `Shmem` wraps a raw mapping; nothing else touches it while the render
+    // thread owns it, so it's safe to move across the thread that created
+    // the ring buffer and the thread driving `render_thread_fbo`.
+    unsafe impl Send for FrameSegment {}
+
+    impl FrameSegment {
+        fn create(os_id: String, size: usize) -> Result<Self, String> {
+            let shmem = ShmemConf::new()
+                .size(size)
+                .os_id(&os_id)
+                .create()
+                .map_err(|e| format!("Failed to create shared-memory segment {}: {}", os_id, e))?;
+            Ok(Self { shmem, os_id })
+        }
+
+        /// Safe because the render thread is the sole writer and the
+        /// frontend only ever reads a segment after receiving the
+        /// `mpv-frame` event for it, i.e. after this write has completed.
+        fn as_mut_slice(&mut self) -> &mut [u8] {
+            unsafe { std::slice::from_raw_parts_mut(self.shmem.as_ptr(), self.shmem.len()) }
+        }
+    }
+
+    /// Small pool of reused shared-memory segments the render thread
+    /// writes frames into, replacing the old read-back -> JPEG-encode ->
+    /// base64-encode -> event pipeline. Double-buffered (`BUFFER_COUNT`
+    /// segments) so the render thread can fill one segment while the
+    /// frontend is still reading the previous one.
+    struct FrameRingBuffer {
+        segments: Vec<FrameSegment>,
+        next: usize,
+        segment_size: usize,
+    }
+
+    impl FrameRingBuffer {
+        const BUFFER_COUNT: usize = 2;
+
+        fn new(segment_size: usize) -> Result<Self, String> {
+            let pid = std::process::id();
+            let segments = (0..Self::BUFFER_COUNT)
+                .map(|i| FrameSegment::create(format!("sbtltv-mpv-frame-{}-{}", pid, i), segment_size))
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(Self { segments, next: 0, segment_size })
+        }
+
+        /// Recreate every segment at (at least) `segment_size` bytes if the
+        /// frame size has grown - e.g. the source changed resolution. The
+        /// frontend notices via the `width`/`height`/`stride` fields on the
+        /// next `mpv-frame` event and remaps before reading.
+        fn resize_if_needed(&mut self, segment_size: usize) -> Result<(), String> {
+            if segment_size <= self.segment_size {
+                return Ok(());
+            }
+            // Drop the old segments (and their OS handles) before recreating
+            // the same `os_id`s at the new size.
+            self.segments.clear();
+            *self = Self::new(segment_size)?;
+            Ok(())
+        }
+
+        /// The segment to fill for this frame, and its index so the
+        /// frontend knows which mapping to (re)read.
+        fn next_segment(&mut self) -> (&mut FrameSegment, usize) {
+            let idx = self.next;
+            self.next = (self.next + 1) % Self::BUFFER_COUNT;
+            (&mut self.segments[idx], idx)
+        }
     }
 
     pub struct FboMpvState {
@@ -514,7 +1243,62 @@ mod fbo_fallback {
         ctx.get_proc_address(name)
     }
 
+    /// Map mpv's reported colorimetry onto our `ColorSpace`/`ColorRange` so
+    /// the YUV conversion matches the source instead of assuming BT.709
+    /// full range for everything.
+    fn mpv_color_params(mpv: &Mpv) -> (ColorSpace, ColorRange) {
+        use crate::mpv::renderer::{ColorRange, ColorSpace};
+
+        let matrix = mpv
+            .get_property::<String>("video-params/colormatrix")
+            .unwrap_or_default();
+        let space = match matrix.as_str() {
+            "bt.601" => ColorSpace::Bt601,
+            "bt.2020-ncl" | "bt.2020-cl" => ColorSpace::Bt2020,
+            _ => ColorSpace::Bt709,
+        };
+
+        let levels = mpv
+            .get_property::<String>("video-params/colorlevels")
+            .unwrap_or_default();
+        let range = if levels == "full" {
+            ColorRange::Full
+        } else {
+            ColorRange::Limited
+        };
+
+        (space, range)
+    }
+
     pub fn init_mpv_fbo(app: &AppHandle) -> Result<(), String> {
+        // Share the main window's surfman display connection with the
+        // render context when we can get one - see
+        // `gl_context::connection_from_window` - and fall back to an
+        // isolated connection (the pre-existing behavior) otherwise, e.g.
+        // on a backend without raw-window-handle support.
+        #[cfg(target_os = "linux")]
+        let (connection, native_widget) = match app.get_webview_window("main") {
+            Some(window) => match crate::mpv::gl_context::connection_from_window(&window) {
+                Ok(conn) => {
+                    let widget = match crate::mpv::gl_context::native_widget_from_window(&conn, &window) {
+                        Ok(widget) => Some(widget),
+                        Err(e) => {
+                            log::warn!("[VIDEO-FBO] Falling back to an offscreen surface: {}", e);
+                            None
+                        }
+                    };
+                    (Some(conn), widget)
+                }
+                Err(e) => {
+                    log::warn!("[VIDEO-FBO] Falling back to a standalone surfman connection: {}", e);
+                    (None, None)
+                }
+            },
+            None => (None, None),
+        };
+        #[cfg(not(target_os = "linux"))]
+        let (connection, native_widget) = (None, None);
+
         let mpv = Mpv::with_initializer(|init| {
             init.set_property("vo", "libmpv")?;
             init.set_property("osc", "no")?;
@@ -537,9 +1321,21 @@ mod fbo_fallback {
         };
         app.manage(state);
 
+        #[cfg(all(feature = "pipewire-screencast", target_os = "linux"))]
+        app.manage(std::sync::Arc::new(crate::mpv::screencast::ScreencastState::new()));
+
+        #[cfg(all(feature = "dmabuf-export", target_os = "linux"))]
+        {
+            let consumer = std::sync::Arc::new(dmabuf::DmabufConsumer::new());
+            if let Err(e) = consumer.clone().listen() {
+                log::warn!("[DMABUF] Failed to start consumer listener: {}", e);
+            }
+            app.manage(consumer);
+        }
+
         let app_handle = app.clone();
         std::thread::spawn(move || {
-            if let Err(e) = render_thread_fbo(mpv, shutdown, app_handle) {
+            if let Err(e) = render_thread_fbo(mpv, shutdown, app_handle, connection, native_widget) {
                 log::error!("[VIDEO-FBO] Render thread error: {}", e);
             }
         });
@@ -551,10 +1347,25 @@ mod fbo_fallback {
         mpv: Arc<Mpv>,
         shutdown: Arc<AtomicBool>,
         app: AppHandle,
+        connection: Option<surfman::Connection>,
+        native_widget: Option<surfman::NativeWidget>,
     ) -> Result<(), String> {
         log::info!("[VIDEO-FBO] Render thread starting...");
 
-        let gl_ctx = HeadlessGLContext::new()?;
+        let gl_ctx = match connection {
+            Some(conn) => HeadlessGLContext::from_connection(conn, native_widget),
+            None => HeadlessGLContext::new(),
+        };
+        let gl_ctx = match gl_ctx {
+            Ok(ctx) => ctx,
+            Err(e) => {
+                log::warn!(
+                    "[VIDEO-FBO] No usable GL context ({}), falling back to the software frame path",
+                    e
+                );
+                return run_software_fallback(mpv, shutdown, app);
+            }
+        };
         gl_ctx.make_current()?;
 
         gl::load_with(|s| gl_ctx.get_proc_address(s) as *const _);
@@ -588,9 +1399,13 @@ mod fbo_fallback {
         let mut offscreen = OffscreenRenderer::new(1920, 1080);
         let fbo_ok = offscreen.is_complete();
 
+        let mut ring = FrameRingBuffer::new((1920 * 1080 * 4) as usize)?;
+
         let _ = app.emit("mpv-ready", true);
 
         let mut last_frame_time = Instant::now();
+        #[cfg(all(feature = "dmabuf-export", target_os = "linux"))]
+        let mut dmabuf_frame_id: u64 = 0;
         let frame_interval = Duration::from_millis(33);
 
         while !shutdown.load(Ordering::SeqCst) {
@@ -605,13 +1420,74 @@ mod fbo_fallback {
                     && last_frame_time.elapsed() >= frame_interval
                 {
                     last_frame_time = Instant::now();
-                    let jpeg_bytes = offscreen.read_as_jpeg(80);
-                    let frame = FrameData {
-                        width: offscreen.width(),
-                        height: offscreen.height(),
-                        jpeg: BASE64.encode(&jpeg_bytes),
-                    };
-                    let _ = app.emit("mpv-frame", frame);
+                    let (space, range) = mpv_color_params(&mpv);
+                    offscreen.set_color_params(space, range);
+
+                    let width = offscreen.width();
+                    let height = offscreen.height();
+                    let stride = width * 4;
+                    let segment_size = (stride * height) as usize;
+
+                    if let Err(e) = ring.resize_if_needed(segment_size) {
+                        log::error!("[VIDEO-FBO] Failed to resize frame ring buffer: {}", e);
+                    } else {
+                        let (segment, segment_id) = ring.next_segment();
+                        offscreen.copy_into(&mut segment.as_mut_slice()[..segment_size]);
+
+                        #[cfg(all(feature = "pipewire-screencast", target_os = "linux"))]
+                        if let Some(screencast) =
+                            app.try_state::<std::sync::Arc<crate::mpv::screencast::ScreencastState>>()
+                        {
+                            screencast.push_frame(width, height, &segment.as_mut_slice()[..segment_size]);
+                        }
+
+                        let frame = FrameData {
+                            segment_id,
+                            os_id: segment.os_id.clone(),
+                            width,
+                            height,
+                            stride,
+                            format: FramePixelFormat::Rgba8,
+                        };
+                        let _ = app.emit("mpv-frame", frame);
+                    }
+
+                    #[cfg(feature = "fbo-jpeg-debug")]
+                    {
+                        let jpeg_bytes = offscreen.read_as_jpeg(80);
+                        let _ = app.emit("mpv-frame-jpeg-debug", BASE64.encode(&jpeg_bytes));
+                    }
+
+                    // Best-effort upgrade over the shared-memory path above:
+                    // if the driver supports it, also hand the frame to a
+                    // connected consumer (a compositor, a Wayland
+                    // subsurface) as a DMABUF it can import with no CPU
+                    // copy at all. The fds only mean anything to that
+                    // consumer, passed via SCM_RIGHTS - see
+                    // `dmabuf::send_frame_to_consumer` - so the frontend
+                    // only ever gets the size/stride metadata, for display
+                    // purposes.
+                    #[cfg(all(feature = "dmabuf-export", target_os = "linux"))]
+                    {
+                        let export = unsafe {
+                            crate::mpv::dmabuf::export_texture(offscreen.color_texture(), width, height)
+                        };
+                        match export {
+                            Ok(frame) => {
+                                dmabuf_frame_id += 1;
+                                let meta = frame.meta(dmabuf_frame_id);
+                                if let Some(consumer) =
+                                    app.try_state::<std::sync::Arc<crate::mpv::dmabuf::DmabufConsumer>>()
+                                {
+                                    consumer.send(frame, dmabuf_frame_id);
+                                }
+                                let _ = app.emit("mpv-frame-dmabuf", meta);
+                            }
+                            Err(e) => {
+                                log::debug!("[VIDEO-FBO] DMABUF export unavailable: {}", e);
+                            }
+                        }
+                    }
                 }
             }
             std::thread::sleep(Duration::from_millis(8));
@@ -619,4 +1495,47 @@ mod fbo_fallback {
 
         Ok(())
     }
+
+    /// Last resort when `HeadlessGLContext` couldn't get a GL context at
+    /// all (the match in `render_thread_fbo` above). Rather than leaving
+    /// the window blank, poll mpv for a screenshot, decode it, and feed
+    /// the raw pixels into `shm_ring`'s lock-free ring for the frontend to
+    /// blit from - much lower frame rate than the GPU path, but it keeps
+    /// video working on machines without usable GL.
+    fn run_software_fallback(mpv: Arc<Mpv>, shutdown: Arc<AtomicBool>, app: AppHandle) -> Result<(), String> {
+        log::warn!("[VIDEO-FBO] Running software frame fallback (no GL context available)");
+
+        let Some(ring) = app.try_state::<crate::shm_ring::ShmRingState>() else {
+            return Err("shm_ring state not initialized".to_string());
+        };
+
+        let screenshot_path = std::env::temp_dir().join(format!("sbtltv-fallback-{}.png", std::process::id()));
+
+        while !shutdown.load(Ordering::SeqCst) {
+            if let Some(path) = screenshot_path.to_str() {
+                if let Err(e) = mpv.command("screenshot-to-file", &[path, "png"]) {
+                    log::debug!("[VIDEO-FBO] Software fallback screenshot failed: {}", e);
+                    std::thread::sleep(Duration::from_millis(200));
+                    continue;
+                }
+
+                match image::open(&screenshot_path) {
+                    Ok(img) => {
+                        let rgba = img.to_rgba8();
+                        let (width, height) = rgba.dimensions();
+                        let pts_micros = (mpv.get_property::<f64>("time-pos").unwrap_or(0.0) * 1_000_000.0) as i64;
+                        if let Err(e) = ring.write_frame(width, height, pts_micros, rgba.as_raw()) {
+                            log::warn!("[VIDEO-FBO] Failed to write software frame to shm ring: {}", e);
+                        }
+                    }
+                    Err(e) => log::debug!("[VIDEO-FBO] Failed to decode fallback screenshot: {}", e),
+                }
+            }
+
+            std::thread::sleep(Duration::from_millis(200));
+        }
+
+        let _ = std::fs::remove_file(&screenshot_path);
+        Ok(())
+    }
 }
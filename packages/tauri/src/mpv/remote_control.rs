@@ -0,0 +1,377 @@
+//! Remote control server: lets a phone or second machine drive playback
+//! over a plain TCP connection carrying tagged-JSON messages, modeled on
+//! `sync_party`'s listener/accept-loop shape. A connecting client must
+//! complete a version/capability handshake before any command is
+//! accepted, so a client built against a future, incompatible wire format
+//! fails loudly instead of sending commands the server can't honor.
+//!
+//! The server binds `0.0.0.0`, so anyone on the LAN can reach it - the
+//! version handshake alone is not authentication. `mpv_remote_start`
+//! therefore also mints a random per-session auth token (the same short
+//! "show this, type/scan that" idea as `p2p::pairing`, just without a
+//! Noise tunnel backing it) that a client must echo in its `Connect`
+//! message before any command is dispatched.
+
+use super::external::ExternalMpv;
+use super::{MpvResult, MpvState, MpvStatus};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+/// Bumped on breaking wire-format changes. The server rejects any client
+/// whose `protocol_version` doesn't match exactly - there's only ever been
+/// one revision of this protocol so far, so any mismatch is a major one.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Commands this server knows how to execute, advertised to clients during
+/// the handshake so one built against a newer server can tell ahead of
+/// time that a capability it wants isn't there yet.
+const SUPPORTED_CAPABILITIES: &[&str] = &["play", "pause", "seek", "volume", "loadfile", "status"];
+
+const STATUS_PUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RemoteMessage {
+    /// First message a client must send; anything else sent first is a
+    /// protocol violation and closes the connection.
+    Connect {
+        protocol_version: u32,
+        capabilities: Vec<String>,
+        auth_token: String,
+    },
+    Welcome {
+        protocol_version: u32,
+        capabilities: Vec<String>,
+    },
+    Rejected {
+        reason: String,
+    },
+    Play,
+    Pause,
+    Seek {
+        time: f64,
+    },
+    SetVolume {
+        volume: f64,
+    },
+    LoadFile {
+        url: String,
+    },
+    /// Start streaming `Status` snapshots back on this connection.
+    Subscribe,
+    Status {
+        #[serde(flatten)]
+        status: MpvStatus,
+    },
+    Error {
+        message: String,
+    },
+}
+
+struct ConnectedClient {
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+/// Remote-control session state: the listener's own accept-loop task and
+/// the set of currently-connected clients, each torn down explicitly by
+/// `mpv_remote_stop` rather than left to drop on their own.
+pub struct RemoteControlState {
+    listener_task: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+    clients: Mutex<HashMap<u64, ConnectedClient>>,
+    next_client_id: AtomicU64,
+    /// Set by `mpv_remote_start` to a fresh random token for that session,
+    /// cleared by `stop`. `None` means the server isn't running, so no
+    /// `Connect` can be accepted regardless of what token it carries.
+    auth_token: Mutex<Option<String>>,
+}
+
+impl RemoteControlState {
+    pub fn new() -> Self {
+        Self {
+            listener_task: Mutex::new(None),
+            clients: Mutex::new(HashMap::new()),
+            next_client_id: AtomicU64::new(1),
+            auth_token: Mutex::new(None),
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        self.listener_task.lock().unwrap().is_some()
+    }
+
+    fn check_token(&self, candidate: &str) -> bool {
+        self.auth_token
+            .lock()
+            .unwrap()
+            .as_deref()
+            .is_some_and(|expected| expected == candidate)
+    }
+
+    /// Abort the accept loop and every connected client's task, leaving no
+    /// background work running once this returns.
+    pub fn stop(&self) {
+        if let Some(task) = self.listener_task.lock().unwrap().take() {
+            task.abort();
+        }
+        for (_, client) in self.clients.lock().unwrap().drain() {
+            client.task.abort();
+        }
+        *self.auth_token.lock().unwrap() = None;
+    }
+}
+
+impl Default for RemoteControlState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn apply_local(mpv: &tauri::State<'_, MpvState>, f: impl FnOnce(&ExternalMpv)) {
+    let guard = mpv.external.lock().unwrap();
+    if let Some(ext) = &guard.mpv {
+        f(ext);
+    }
+}
+
+fn current_status(app: &AppHandle) -> Option<MpvStatus> {
+    let mpv = app.try_state::<MpvState>()?;
+    let guard = mpv.external.lock().unwrap();
+    guard.mpv.as_ref().map(|ext| ext.get_status())
+}
+
+fn status_shutdown_flag(mpv: &tauri::State<'_, MpvState>) -> Option<Arc<AtomicBool>> {
+    let guard = mpv.external.lock().unwrap();
+    guard.mpv.as_ref().map(|ext| ext.shutdown_flag())
+}
+
+fn dispatch_command(app: &AppHandle, msg: RemoteMessage) {
+    let Some(mpv) = app.try_state::<MpvState>() else {
+        return;
+    };
+
+    match msg {
+        RemoteMessage::Play => apply_local(&mpv, |ext| {
+            let _ = ext.play();
+        }),
+        RemoteMessage::Pause => apply_local(&mpv, |ext| {
+            let _ = ext.pause();
+        }),
+        RemoteMessage::Seek { time } => apply_local(&mpv, |ext| {
+            let _ = ext.seek(time);
+        }),
+        RemoteMessage::SetVolume { volume } => apply_local(&mpv, |ext| {
+            let _ = ext.set_volume(volume);
+        }),
+        RemoteMessage::LoadFile { url } => apply_local(&mpv, |ext| {
+            let _ = ext.load(&url);
+        }),
+        _ => {}
+    }
+}
+
+async fn write_message(writer: &mut OwnedWriteHalf, msg: &RemoteMessage) -> std::io::Result<()> {
+    let json = serde_json::to_string(msg).unwrap_or_default();
+    writer.write_all(format!("{}\n", json).as_bytes()).await
+}
+
+/// Push `MpvStatus` snapshots to a subscribed client until the underlying
+/// `ExternalMpv` shuts down or the client disconnects (at which point
+/// `write_message` in the owning connection's write task starts failing,
+/// drops `tx`, and this loop's `send` starts failing too).
+fn spawn_status_stream(tx: UnboundedSender<RemoteMessage>, app: AppHandle, shutdown: Arc<AtomicBool>) {
+    tauri::async_runtime::spawn(async move {
+        while !shutdown.load(Ordering::SeqCst) {
+            if let Some(status) = current_status(&app) {
+                if tx.send(RemoteMessage::Status { status }).is_err() {
+                    break;
+                }
+            }
+            tokio::time::sleep(STATUS_PUSH_INTERVAL).await;
+        }
+    });
+}
+
+/// Owns one client connection for its lifetime: performs the handshake,
+/// then pumps outbound messages onto the socket while dispatching inbound
+/// commands onto `ExternalMpv`.
+async fn handle_client(stream: TcpStream, app: AppHandle, state: Arc<RemoteControlState>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    let handshake = match lines.next_line().await {
+        Ok(Some(line)) => serde_json::from_str::<RemoteMessage>(&line).ok(),
+        _ => None,
+    };
+
+    let accepted = match handshake {
+        Some(RemoteMessage::Connect { protocol_version, auth_token, .. })
+            if protocol_version == PROTOCOL_VERSION && state.check_token(&auth_token) =>
+        {
+            true
+        }
+        Some(RemoteMessage::Connect { protocol_version, .. }) if protocol_version != PROTOCOL_VERSION => {
+            let _ = write_message(
+                &mut writer,
+                &RemoteMessage::Rejected {
+                    reason: format!(
+                        "Protocol version mismatch: server speaks v{}, client requested v{}",
+                        PROTOCOL_VERSION, protocol_version
+                    ),
+                },
+            )
+            .await;
+            false
+        }
+        Some(RemoteMessage::Connect { .. }) => {
+            let _ = write_message(
+                &mut writer,
+                &RemoteMessage::Rejected {
+                    reason: "Invalid or missing auth token".to_string(),
+                },
+            )
+            .await;
+            false
+        }
+        _ => {
+            let _ = write_message(
+                &mut writer,
+                &RemoteMessage::Rejected {
+                    reason: "Expected a Connect handshake as the first message".to_string(),
+                },
+            )
+            .await;
+            false
+        }
+    };
+
+    if !accepted {
+        return;
+    }
+
+    let _ = write_message(
+        &mut writer,
+        &RemoteMessage::Welcome {
+            protocol_version: PROTOCOL_VERSION,
+            capabilities: SUPPORTED_CAPABILITIES.iter().map(|s| s.to_string()).collect(),
+        },
+    )
+    .await;
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<RemoteMessage>();
+    let write_task = tauri::async_runtime::spawn(async move {
+        while let Some(msg) = rx.recv().await {
+            if write_message(&mut writer, &msg).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut subscribed = false;
+
+    loop {
+        match lines.next_line().await {
+            Ok(Some(line)) if !line.trim().is_empty() => match serde_json::from_str::<RemoteMessage>(&line) {
+                Ok(RemoteMessage::Subscribe) => {
+                    if !subscribed {
+                        if let Some(mpv) = app.try_state::<MpvState>() {
+                            if let Some(flag) = status_shutdown_flag(&mpv) {
+                                subscribed = true;
+                                spawn_status_stream(tx.clone(), app.clone(), flag);
+                            }
+                        }
+                    }
+                }
+                Ok(msg) => dispatch_command(&app, msg),
+                Err(_) => {
+                    let _ = tx.send(RemoteMessage::Error {
+                        message: "Malformed message".to_string(),
+                    });
+                }
+            },
+            Ok(Some(_)) => continue,
+            Ok(None) | Err(_) => break,
+        }
+    }
+
+    write_task.abort();
+}
+
+/// Start the remote-control listener on `port`. Accepts connections for
+/// the lifetime of the app, or until `mpv_remote_stop` tears it down.
+/// Mints a fresh auth token for this session - call `mpv_remote_auth_token`
+/// to fetch it for display (text/QR) on the host so the remote client can
+/// be told what to send back in its `Connect` message.
+#[tauri::command]
+pub async fn mpv_remote_start(
+    port: u16,
+    app: AppHandle,
+    state: tauri::State<'_, Arc<RemoteControlState>>,
+) -> Result<MpvResult, ()> {
+    let state = state.inner().clone();
+    if state.is_running() {
+        return Ok(MpvResult::err("Remote control server already running"));
+    }
+
+    let addr = format!("0.0.0.0:{}", port);
+    let listener = match TcpListener::bind(&addr).await {
+        Ok(l) => l,
+        Err(e) => return Ok(MpvResult::err(format!("Failed to bind {}: {}", addr, e))),
+    };
+
+    log::info!("[REMOTE] Listening on {}", addr);
+
+    *state.auth_token.lock().unwrap() = Some(generate_auth_token());
+
+    let accept_state = state.clone();
+    let accept_app = app.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer_addr)) => {
+                    log::info!("[REMOTE] Client connected: {}", peer_addr);
+                    let client_id = accept_state.next_client_id.fetch_add(1, Ordering::SeqCst);
+                    let client_state = accept_state.clone();
+                    let client_app = accept_app.clone();
+                    let handle = tauri::async_runtime::spawn(async move {
+                        handle_client(stream, client_app, client_state.clone()).await;
+                        client_state.clients.lock().unwrap().remove(&client_id);
+                    });
+                    accept_state.clients.lock().unwrap().insert(client_id, ConnectedClient { task: handle });
+                }
+                Err(e) => log::warn!("[REMOTE] Accept failed: {}", e),
+            }
+        }
+    });
+
+    *state.listener_task.lock().unwrap() = Some(task);
+    Ok(MpvResult::ok())
+}
+
+/// Stop the remote-control server, dropping every connected client.
+#[tauri::command]
+pub fn mpv_remote_stop(state: tauri::State<'_, Arc<RemoteControlState>>) -> MpvResult {
+    state.stop();
+    MpvResult::ok()
+}
+
+/// The current session's auth token, or `None` if the server isn't
+/// running. The frontend shows this (e.g. as a QR code) for the remote
+/// client to echo back in its `Connect` message.
+#[tauri::command]
+pub fn mpv_remote_auth_token(state: tauri::State<'_, Arc<RemoteControlState>>) -> Option<String> {
+    state.auth_token.lock().unwrap().clone()
+}
+
+fn generate_auth_token() -> String {
+    let bytes: [u8; 16] = rand::thread_rng().gen();
+    hex::encode(bytes)
+}
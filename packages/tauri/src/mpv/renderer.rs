@@ -1,4 +1,129 @@
 use gl::types::*;
+#[cfg(feature = "fbo-jpeg-debug")]
+use image::codecs::jpeg::JpegEncoder;
+
+/// RGB<->YUV conversion matrix, selected to match the source material
+/// rather than assuming one coefficient set for everything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSpace {
+    /// SD content.
+    Bt601,
+    /// HD content (the renderer's previous hardcoded default).
+    Bt709,
+    /// UHD/HDR content.
+    Bt2020,
+}
+
+/// Whether Y/UV values are scaled to the "TV"/limited range (Y: 16-235,
+/// UV: 16-240) or use the full 0-255 range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorRange {
+    Limited,
+    Full,
+}
+
+/// RGB->Y and RGB->U/V coefficient rows plus the range scale applied
+/// afterwards. Y is scaled directly; U/V are recentered around 128 before
+/// scaling so limited range narrows the excursion without shifting the
+/// neutral (colorless) midpoint.
+struct YuvMatrix {
+    y: [f32; 3],
+    u: [f32; 3],
+    v: [f32; 3],
+    y_scale: f32,
+    y_offset: f32,
+    uv_scale: f32,
+}
+
+impl ColorSpace {
+    fn coeffs(self) -> ([f32; 3], [f32; 3], [f32; 3]) {
+        match self {
+            ColorSpace::Bt601 => (
+                [0.299, 0.587, 0.114],
+                [-0.1687, -0.3313, 0.5],
+                [0.5, -0.4187, -0.0813],
+            ),
+            ColorSpace::Bt709 => (
+                [0.2126, 0.7152, 0.0722],
+                [-0.1146, -0.3854, 0.5],
+                [0.5, -0.4542, -0.0458],
+            ),
+            ColorSpace::Bt2020 => (
+                [0.2627, 0.6780, 0.0593],
+                [-0.1396, -0.3604, 0.5],
+                [0.5, -0.4598, -0.0402],
+            ),
+        }
+    }
+}
+
+impl ColorRange {
+    /// (y_scale, y_offset, uv_scale) mapping full-range intermediate values
+    /// (Y: 0-255, UV: 0-255 centered at 128) onto this range.
+    fn scale_offset(self) -> (f32, f32, f32) {
+        match self {
+            ColorRange::Full => (1.0, 0.0, 1.0),
+            // 16-235 for luma, 16-240 for chroma (centered at 128).
+            ColorRange::Limited => (219.0 / 255.0, 16.0, 224.0 / 255.0),
+        }
+    }
+}
+
+fn build_matrix(space: ColorSpace, range: ColorRange) -> YuvMatrix {
+    let (y, u, v) = space.coeffs();
+    let (y_scale, y_offset, uv_scale) = range.scale_offset();
+    YuvMatrix { y, u, v, y_scale, y_offset, uv_scale }
+}
+
+impl YuvMatrix {
+    /// Fold the range scale into each coefficient row so the result is a
+    /// plain `dot(rgb, coeffs.rgb) + coeffs.a` the GPU shader can apply
+    /// directly, matching the CPU path's `y_full * scale + offset` exactly.
+    fn gpu_coeffs(&self) -> ([f32; 4], [f32; 4], [f32; 4]) {
+        let y = [
+            self.y[0] * self.y_scale,
+            self.y[1] * self.y_scale,
+            self.y[2] * self.y_scale,
+            self.y_offset,
+        ];
+        let u = [
+            self.u[0] * self.uv_scale,
+            self.u[1] * self.uv_scale,
+            self.u[2] * self.uv_scale,
+            128.0,
+        ];
+        let v = [
+            self.v[0] * self.uv_scale,
+            self.v[1] * self.uv_scale,
+            self.v[2] * self.uv_scale,
+            128.0,
+        ];
+        (y, u, v)
+    }
+}
+
+/// A sub-rectangle of the framebuffer, in top-left-origin pixel
+/// coordinates (matching how the frontend blits regions back onto its
+/// canvas). Used for damage-rectangle readback of mostly-static frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DamageRect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl DamageRect {
+    /// Clamp the rect to the frame bounds and align it to even
+    /// boundaries so 2x2 chroma subsampling stays correct at the edges.
+    fn clamp_to(self, frame_w: u32, frame_h: u32) -> Self {
+        let x = (self.x & !1).min(frame_w.saturating_sub(2));
+        let y = (self.y & !1).min(frame_h.saturating_sub(2));
+        let w = ((self.w + 1) & !1).min(frame_w - x).max(2);
+        let h = ((self.h + 1) & !1).min(frame_h - y).max(2);
+        Self { x, y, w, h }
+    }
+}
 
 /// Manages an OpenGL FBO for offscreen mpv rendering
 /// and reads back pixel data for transfer to the frontend.
@@ -8,6 +133,18 @@ pub struct OffscreenRenderer {
     width: u32,
     height: u32,
     pixel_buffer: Vec<u8>,
+    color_space: ColorSpace,
+    color_range: ColorRange,
+    /// Double-buffered PBOs for async readback: while PBO[i] is being
+    /// filled by this frame's glReadPixels, PBO[(i+1)%2] holds last
+    /// frame's data and is safe to map without stalling the GPU.
+    pbos: [GLuint; 2],
+    pbo_index: usize,
+    /// True once a frame has been read back, so the first call to
+    /// `read_pixels` (no previous buffer yet) can return the cleared buffer.
+    has_prev_frame: bool,
+    #[cfg(feature = "gpu-yuv")]
+    gpu_yuv: Option<super::yuv_gpu::GpuYuvConverter>,
 }
 
 impl OffscreenRenderer {
@@ -52,15 +189,50 @@ impl OffscreenRenderer {
         }
 
         let buf_size = (width * height * 4) as usize;
+        let pbos = Self::create_pbos(buf_size);
+
         Self {
             fbo,
             texture,
             width,
             height,
             pixel_buffer: vec![0u8; buf_size],
+            color_space: ColorSpace::Bt709,
+            color_range: ColorRange::Full,
+            pbos,
+            pbo_index: 0,
+            has_prev_frame: false,
+            #[cfg(feature = "gpu-yuv")]
+            gpu_yuv: None,
         }
     }
 
+    /// Set the color matrix and range used by `read_as_yuv420`/`read_as_nv12`.
+    /// Call this with mpv's reported colorimetry (`video-params/colormatrix`,
+    /// `video-params/colorlevels`) so the conversion matches the source.
+    pub fn set_color_params(&mut self, space: ColorSpace, range: ColorRange) {
+        self.color_space = space;
+        self.color_range = range;
+    }
+
+    fn create_pbos(buf_size: usize) -> [GLuint; 2] {
+        let mut pbos = [0; 2];
+        unsafe {
+            gl::GenBuffers(2, pbos.as_mut_ptr());
+            for pbo in pbos {
+                gl::BindBuffer(gl::PIXEL_PACK_BUFFER, pbo);
+                gl::BufferData(
+                    gl::PIXEL_PACK_BUFFER,
+                    buf_size as isize,
+                    std::ptr::null(),
+                    gl::STREAM_READ,
+                );
+            }
+            gl::BindBuffer(gl::PIXEL_PACK_BUFFER, 0);
+        }
+        pbos
+    }
+
     pub fn is_complete(&self) -> bool {
         unsafe {
             gl::BindFramebuffer(gl::FRAMEBUFFER, self.fbo);
@@ -74,6 +246,15 @@ impl OffscreenRenderer {
         self.fbo
     }
 
+    /// The FBO's bound color attachment. Exposed for the DMABUF export
+    /// path (`dmabuf::export_texture`), which needs the raw texture id to
+    /// wrap it in an `EGLImage` - everything else should go through
+    /// `copy_into`/`read_as_jpeg` instead of touching the texture directly.
+    #[cfg(all(feature = "dmabuf-export", target_os = "linux"))]
+    pub fn color_texture(&self) -> GLuint {
+        self.texture
+    }
+
     pub fn width(&self) -> u32 {
         self.width
     }
@@ -90,7 +271,8 @@ impl OffscreenRenderer {
 
         self.width = width;
         self.height = height;
-        self.pixel_buffer.resize((width * height * 4) as usize, 0);
+        let buf_size = (width * height * 4) as usize;
+        self.pixel_buffer.resize(buf_size, 0);
 
         unsafe {
             gl::BindTexture(gl::TEXTURE_2D, self.texture);
@@ -105,15 +287,31 @@ impl OffscreenRenderer {
                 gl::UNSIGNED_BYTE,
                 std::ptr::null(),
             );
+
+            gl::DeleteBuffers(2, self.pbos.as_ptr());
+        }
+        self.pbos = Self::create_pbos(buf_size);
+        self.pbo_index = 0;
+        self.has_prev_frame = false;
+
+        #[cfg(feature = "gpu-yuv")]
+        if let Some(gpu_yuv) = &mut self.gpu_yuv {
+            gpu_yuv.resize(width, height);
         }
 
         log::info!("FBO resized to {}x{}", width, height);
     }
 
-    /// Read back pixels from the FBO into the internal buffer.
+    /// Kick off an async readback of the FBO into PBO[i] and, if a previous
+    /// frame's PBO is ready, map it into `pixel_buffer`. This pipelines the
+    /// GPU→CPU transfer against rendering instead of stalling on every frame.
     pub fn read_pixels(&mut self) {
+        let next_index = (self.pbo_index + 1) % 2;
+
         unsafe {
             gl::BindFramebuffer(gl::FRAMEBUFFER, self.fbo);
+
+            gl::BindBuffer(gl::PIXEL_PACK_BUFFER, self.pbos[self.pbo_index]);
             gl::ReadPixels(
                 0,
                 0,
@@ -121,13 +319,81 @@ impl OffscreenRenderer {
                 self.height as GLsizei,
                 gl::RGBA,
                 gl::UNSIGNED_BYTE,
-                self.pixel_buffer.as_mut_ptr() as *mut _,
+                std::ptr::null_mut(),
             );
+
+            if self.has_prev_frame {
+                gl::BindBuffer(gl::PIXEL_PACK_BUFFER, self.pbos[next_index]);
+                let size = self.pixel_buffer.len();
+                let ptr = gl::MapBufferRange(
+                    gl::PIXEL_PACK_BUFFER,
+                    0,
+                    size as isize,
+                    gl::MAP_READ_BIT,
+                );
+                if !ptr.is_null() {
+                    std::ptr::copy_nonoverlapping(
+                        ptr as *const u8,
+                        self.pixel_buffer.as_mut_ptr(),
+                        size,
+                    );
+                    gl::UnmapBuffer(gl::PIXEL_PACK_BUFFER);
+                }
+            }
+
+            gl::BindBuffer(gl::PIXEL_PACK_BUFFER, 0);
             gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
         }
+
+        self.has_prev_frame = true;
+        self.pbo_index = next_index;
+    }
+
+    /// Read back the FBO and write flipped (top-down) RGBA bytes directly
+    /// into `dst`, which must be at least `width() * height() * 4` bytes.
+    /// Used by the zero-copy shared-memory frame path so a frame goes
+    /// straight from the PBO into the segment the frontend reads, with no
+    /// intermediate `Vec` beyond `pixel_buffer` itself.
+    pub fn copy_into(&mut self, dst: &mut [u8]) {
+        self.read_pixels();
+
+        let w = self.width as usize;
+        let h = self.height as usize;
+        let stride = w * 4;
+
+        for row in 0..h {
+            // Flip vertically: OpenGL reads bottom-up.
+            let src_row = h - 1 - row;
+            let src = &self.pixel_buffer[src_row * stride..(src_row + 1) * stride];
+            dst[row * stride..(row + 1) * stride].copy_from_slice(src);
+        }
     }
 
-    /// Read pixels and convert RGBA to YUV420 planes.
+    /// Read back the FBO and JPEG-encode it at `quality` (0-100). Kept only
+    /// for side-by-side debugging against the zero-copy path - production
+    /// frame delivery goes through `copy_into` instead.
+    #[cfg(feature = "fbo-jpeg-debug")]
+    pub fn read_as_jpeg(&mut self, quality: u8) -> Vec<u8> {
+        self.read_pixels();
+
+        let w = self.width as usize;
+        let h = self.height as usize;
+        let stride = w * 4;
+        let mut rgba = vec![0u8; stride * h];
+        for row in 0..h {
+            let src_row = h - 1 - row;
+            let src = &self.pixel_buffer[src_row * stride..(src_row + 1) * stride];
+            rgba[row * stride..(row + 1) * stride].copy_from_slice(src);
+        }
+
+        let mut out = Vec::new();
+        let encoder = JpegEncoder::new_with_quality(&mut out, quality);
+        let _ = encoder.write_image(&rgba, self.width, self.height, image::ExtendedColorType::Rgba8);
+        out
+    }
+
+    /// Read pixels and convert RGBA to YUV420 planes, using the renderer's
+    /// configured color space and range (see `set_color_params`).
     /// Returns (y_plane, u_plane, v_plane) for efficient transfer.
     pub fn read_as_yuv420(&mut self) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
         self.read_pixels();
@@ -142,8 +408,8 @@ impl OffscreenRenderer {
         let mut v_plane = vec![0u8; half_w * half_h];
 
         let rgba = &self.pixel_buffer;
+        let m = build_matrix(self.color_space, self.color_range);
 
-        // Convert RGBA to YUV420 (BT.709)
         // Y for every pixel, U/V subsampled 2x2
         for row in 0..h {
             // Flip vertically: OpenGL reads bottom-up
@@ -154,14 +420,16 @@ impl OffscreenRenderer {
                 let g = rgba[idx + 1] as f32;
                 let b = rgba[idx + 2] as f32;
 
-                // BT.709 RGB→YUV
-                let y = (0.2126 * r + 0.7152 * g + 0.0722 * b).clamp(0.0, 255.0);
+                let y_full = m.y[0] * r + m.y[1] * g + m.y[2] * b;
+                let y = (y_full * m.y_scale + m.y_offset).clamp(0.0, 255.0);
                 y_plane[row * w + col] = y as u8;
 
                 // Subsample U/V at 2x2 blocks (top-left pixel of each block)
                 if row % 2 == 0 && col % 2 == 0 {
-                    let u = (-0.1146 * r - 0.3854 * g + 0.5 * b + 128.0).clamp(0.0, 255.0);
-                    let v = (0.5 * r - 0.4542 * g - 0.0458 * b + 128.0).clamp(0.0, 255.0);
+                    let u_full = m.u[0] * r + m.u[1] * g + m.u[2] * b + 128.0;
+                    let v_full = m.v[0] * r + m.v[1] * g + m.v[2] * b + 128.0;
+                    let u = ((u_full - 128.0) * m.uv_scale + 128.0).clamp(0.0, 255.0);
+                    let v = ((v_full - 128.0) * m.uv_scale + 128.0).clamp(0.0, 255.0);
                     let uv_idx = (row / 2) * half_w + (col / 2);
                     u_plane[uv_idx] = u as u8;
                     v_plane[uv_idx] = v as u8;
@@ -171,6 +439,184 @@ impl OffscreenRenderer {
 
         (y_plane, u_plane, v_plane)
     }
+
+    /// Read pixels and convert RGBA to NV12: a full-resolution Y plane
+    /// followed by a half-resolution interleaved `[U, V, U, V, ...]`
+    /// chroma plane. This matches what WebCodecs `VideoFrame` and most
+    /// hardware encoders expect, avoiding a plane-merge step in JS.
+    /// Shares the flip/subsample logic and color matrix with
+    /// `read_as_yuv420`.
+    pub fn read_as_nv12(&mut self) -> (Vec<u8>, Vec<u8>) {
+        self.read_pixels();
+
+        let w = self.width as usize;
+        let h = self.height as usize;
+        let half_w = w / 2;
+        let half_h = h / 2;
+
+        let mut y_plane = vec![0u8; w * h];
+        let mut uv_plane = vec![0u8; half_w * half_h * 2];
+
+        let rgba = &self.pixel_buffer;
+        let m = build_matrix(self.color_space, self.color_range);
+
+        for row in 0..h {
+            // Flip vertically: OpenGL reads bottom-up
+            let src_row = h - 1 - row;
+            for col in 0..w {
+                let idx = (src_row * w + col) * 4;
+                let r = rgba[idx] as f32;
+                let g = rgba[idx + 1] as f32;
+                let b = rgba[idx + 2] as f32;
+
+                let y_full = m.y[0] * r + m.y[1] * g + m.y[2] * b;
+                let y = (y_full * m.y_scale + m.y_offset).clamp(0.0, 255.0);
+                y_plane[row * w + col] = y as u8;
+
+                if row % 2 == 0 && col % 2 == 0 {
+                    let u_full = m.u[0] * r + m.u[1] * g + m.u[2] * b + 128.0;
+                    let v_full = m.v[0] * r + m.v[1] * g + m.v[2] * b + 128.0;
+                    let u = ((u_full - 128.0) * m.uv_scale + 128.0).clamp(0.0, 255.0);
+                    let v = ((v_full - 128.0) * m.uv_scale + 128.0).clamp(0.0, 255.0);
+                    let uv_idx = ((row / 2) * half_w + (col / 2)) * 2;
+                    uv_plane[uv_idx] = u as u8;
+                    uv_plane[uv_idx + 1] = v as u8;
+                }
+            }
+        }
+
+        (y_plane, uv_plane)
+    }
+
+    /// The full-frame rect, for callers that want a no-op damage region.
+    pub fn full_rect(&self) -> DamageRect {
+        DamageRect { x: 0, y: 0, w: self.width, h: self.height }
+    }
+
+    /// Read back only a sub-rectangle of the FBO (e.g. mpv's reported
+    /// damage region, or a cheap previous-frame diff), avoiding a full
+    /// `glReadPixels` for mostly-static content. Returns tightly-packed
+    /// RGBA bytes for the rect plus the rect actually read, which is
+    /// clamped and even-aligned and so may be slightly larger than
+    /// requested.
+    pub fn read_pixels_region(&mut self, rect: DamageRect) -> (Vec<u8>, DamageRect) {
+        let rect = rect.clamp_to(self.width, self.height);
+        let mut buf = vec![0u8; (rect.w * rect.h * 4) as usize];
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.fbo);
+            // glReadPixels' y origin is bottom-left; flip so `rect.y` means
+            // "distance from the top", matching the frontend's coordinates.
+            let gl_y = self.height - rect.y - rect.h;
+            gl::ReadPixels(
+                rect.x as GLint,
+                gl_y as GLint,
+                rect.w as GLsizei,
+                rect.h as GLsizei,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                buf.as_mut_ptr() as *mut _,
+            );
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+
+        (buf, rect)
+    }
+
+    /// Damage-rectangle equivalent of `read_as_yuv420`: converts only the
+    /// given sub-rectangle, returning plane data sized to the (possibly
+    /// clamped/aligned) rect alongside that rect so the frontend can blit
+    /// just the updated region instead of the whole frame.
+    pub fn read_as_yuv420_region(
+        &mut self,
+        rect: DamageRect,
+    ) -> (Vec<u8>, Vec<u8>, Vec<u8>, DamageRect) {
+        let (rgba, rect) = self.read_pixels_region(rect);
+        let m = build_matrix(self.color_space, self.color_range);
+
+        let w = rect.w as usize;
+        let h = rect.h as usize;
+        let half_w = w / 2;
+        let half_h = h / 2;
+
+        let mut y_plane = vec![0u8; w * h];
+        let mut u_plane = vec![0u8; half_w * half_h];
+        let mut v_plane = vec![0u8; half_w * half_h];
+
+        for row in 0..h {
+            // glReadPixels already returned rows bottom-up for this rect;
+            // flip within the rect the same way the full-frame path does.
+            let src_row = h - 1 - row;
+            for col in 0..w {
+                let idx = (src_row * w + col) * 4;
+                let r = rgba[idx] as f32;
+                let g = rgba[idx + 1] as f32;
+                let b = rgba[idx + 2] as f32;
+
+                let y_full = m.y[0] * r + m.y[1] * g + m.y[2] * b;
+                let y = (y_full * m.y_scale + m.y_offset).clamp(0.0, 255.0);
+                y_plane[row * w + col] = y as u8;
+
+                if row % 2 == 0 && col % 2 == 0 {
+                    let u_full = m.u[0] * r + m.u[1] * g + m.u[2] * b + 128.0;
+                    let v_full = m.v[0] * r + m.v[1] * g + m.v[2] * b + 128.0;
+                    let u = ((u_full - 128.0) * m.uv_scale + 128.0).clamp(0.0, 255.0);
+                    let v = ((v_full - 128.0) * m.uv_scale + 128.0).clamp(0.0, 255.0);
+                    let uv_idx = (row / 2) * half_w + (col / 2);
+                    u_plane[uv_idx] = u as u8;
+                    v_plane[uv_idx] = v as u8;
+                }
+            }
+        }
+
+        (y_plane, u_plane, v_plane, rect)
+    }
+
+    /// GPU-accelerated equivalent of `read_as_yuv420`: runs the color
+    /// conversion as fragment-shader passes instead of a per-pixel CPU
+    /// loop, then reads the resulting R8 plane textures back.
+    #[cfg(feature = "gpu-yuv")]
+    pub fn read_as_yuv420_gpu(&mut self) -> Result<(Vec<u8>, Vec<u8>, Vec<u8>), String> {
+        if self.gpu_yuv.is_none() {
+            self.gpu_yuv = Some(super::yuv_gpu::GpuYuvConverter::new(self.width, self.height)?);
+        }
+
+        // Derive the Y/U/V coefficient rows from the renderer's configured
+        // color space and range, same as the CPU path.
+        let m = build_matrix(self.color_space, self.color_range);
+        let (y_coeffs, u_coeffs, v_coeffs) = m.gpu_coeffs();
+
+        let gpu_yuv = self.gpu_yuv.as_mut().unwrap();
+        let (y_tex, u_tex, v_tex) = gpu_yuv.convert(self.texture, y_coeffs, u_coeffs, v_coeffs);
+
+        let w = self.width as usize;
+        let h = self.height as usize;
+        let half_w = (w / 2).max(1);
+        let half_h = (h / 2).max(1);
+
+        let y_plane = Self::read_r8_texture(y_tex, w, h);
+        let u_plane = Self::read_r8_texture(u_tex, half_w, half_h);
+        let v_plane = Self::read_r8_texture(v_tex, half_w, half_h);
+
+        Ok((y_plane, u_plane, v_plane))
+    }
+
+    #[cfg(feature = "gpu-yuv")]
+    fn read_r8_texture(texture: GLuint, width: usize, height: usize) -> Vec<u8> {
+        let mut buf = vec![0u8; width * height];
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, texture);
+            gl::GetTexImage(
+                gl::TEXTURE_2D,
+                0,
+                gl::RED,
+                gl::UNSIGNED_BYTE,
+                buf.as_mut_ptr() as *mut _,
+            );
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+        buf
+    }
 }
 
 impl Drop for OffscreenRenderer {
@@ -178,6 +624,7 @@ impl Drop for OffscreenRenderer {
         unsafe {
             gl::DeleteFramebuffers(1, &self.fbo);
             gl::DeleteTextures(1, &self.texture);
+            gl::DeleteBuffers(2, self.pbos.as_ptr());
         }
     }
 }
@@ -0,0 +1,208 @@
+//! Optional PipeWire virtual-camera output (Linux only).
+//!
+//! Publishes the frames `render_thread_fbo` already produces for the
+//! shared-memory path (see `mod::FrameRingBuffer`) as a PipeWire stream
+//! node, so other applications - recorders, conferencing tools,
+//! compositors - can consume sbtlTV's decoded video as a virtual camera
+//! without re-decoding anything themselves. Buffers are fed as CPU-mapped
+//! RGBA for now; a DMABUF-backed path next to `dmabuf::export_texture`
+//! is the natural upgrade once GPU-import and PipeWire's SPA buffer
+//! negotiation agree on a format both sides support.
+//!
+//! `mpv_start_screencast`/`mpv_stop_screencast` own the PipeWire main loop
+//! on a dedicated thread, same shape as `thumbnail`'s render thread:
+//! `ScreencastState` just tracks whether it's running and a channel to
+//! feed it frames.
+
+use pipewire::context::Context;
+use pipewire::main_loop::MainLoop;
+use pipewire::properties::properties;
+use pipewire::spa::param::video::VideoFormat;
+use pipewire::spa::pod::serialize::PodSerializer;
+use pipewire::spa::pod::{self, Pod};
+use pipewire::spa::utils::{Direction, Fraction, Rectangle};
+use pipewire::stream::{Stream, StreamFlags};
+use std::io::Cursor;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, Sender, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tauri::State;
+
+use super::MpvResult;
+
+/// One frame handed to the PipeWire thread to publish, in the same
+/// flipped top-down RGBA layout `OffscreenRenderer::copy_into` produces.
+struct ScreencastFrame {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+/// Tracks the running screencast, if any: `render_thread_fbo` pushes
+/// frames through `frame_tx` when it's `Some`, and `mpv_stop_screencast`
+/// signals `shutdown` and joins `thread` to tear the PipeWire node down.
+pub struct ScreencastState {
+    frame_tx: Mutex<Option<SyncSender<ScreencastFrame>>>,
+    shutdown: Arc<AtomicBool>,
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl ScreencastState {
+    pub fn new() -> Self {
+        Self { frame_tx: Mutex::new(None), shutdown: Arc::new(AtomicBool::new(false)), thread: Mutex::new(None) }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.frame_tx.lock().unwrap().is_some()
+    }
+
+    /// Push a freshly rendered frame to the PipeWire thread, if a
+    /// screencast is currently running. Never blocks the render thread -
+    /// a full channel just drops the frame, same as `FrameRingBuffer`
+    /// dropping the oldest segment on overrun.
+    pub fn push_frame(&self, width: u32, height: u32, pixels: &[u8]) {
+        if let Some(tx) = self.frame_tx.lock().unwrap().as_ref() {
+            let _ = tx.try_send(ScreencastFrame { width, height, pixels: pixels.to_vec() });
+        }
+    }
+
+    fn start(&self, width: u32, height: u32) -> Result<u32, String> {
+        if self.is_running() {
+            return Err("Screencast already running".to_string());
+        }
+
+        let (frame_tx, frame_rx) = sync_channel(2);
+        let (node_id_tx, node_id_rx) = std::sync::mpsc::channel();
+        self.shutdown.store(false, Ordering::SeqCst);
+        let shutdown = self.shutdown.clone();
+
+        let handle = std::thread::spawn(move || {
+            run_pipewire_thread(width, height, frame_rx, shutdown, node_id_tx);
+        });
+
+        let node_id = node_id_rx
+            .recv_timeout(Duration::from_secs(5))
+            .map_err(|_| "PipeWire node never came up".to_string())??;
+
+        *self.frame_tx.lock().unwrap() = Some(frame_tx);
+        *self.thread.lock().unwrap() = Some(handle);
+        Ok(node_id)
+    }
+
+    fn stop(&self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        *self.frame_tx.lock().unwrap() = None;
+        if let Some(handle) = self.thread.lock().unwrap().take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Runs the PipeWire main loop and the video stream node for the lifetime
+/// of a screencast. Owns everything PipeWire-side so it can all be
+/// dropped together when `shutdown` is observed.
+fn run_pipewire_thread(
+    width: u32,
+    height: u32,
+    frame_rx: Receiver<ScreencastFrame>,
+    shutdown: Arc<AtomicBool>,
+    node_id_tx: Sender<Result<u32, String>>,
+) {
+    let setup = (|| -> Result<(MainLoop, Stream), String> {
+        let main_loop = MainLoop::new(None).map_err(|e| format!("PipeWire main loop failed: {}", e))?;
+        let context = Context::new(&main_loop).map_err(|e| format!("PipeWire context failed: {}", e))?;
+        let core = context.connect(None).map_err(|e| format!("PipeWire connect failed: {}", e))?;
+
+        let props = properties! {
+            *pipewire::keys::MEDIA_CLASS => "Video/Source",
+            *pipewire::keys::MEDIA_ROLE => "Camera",
+            *pipewire::keys::NODE_NAME => "sbtltv-screencast",
+            *pipewire::keys::NODE_DESCRIPTION => "sbtlTV decoded video",
+        };
+        let stream = Stream::new(&core, "sbtltv-screencast", props)
+            .map_err(|e| format!("PipeWire stream creation failed: {}", e))?;
+
+        let format_pod = build_video_format_pod(width, height)?;
+
+        let frames = Mutex::new(frame_rx);
+        stream
+            .add_local_listener::<()>()
+            .process(move |stream, _| {
+                let Some(frame) = frames.lock().unwrap().try_recv().ok() else { return };
+                if let Some(mut buffer) = stream.dequeue_buffer() {
+                    if let Some(data) = buffer.datas_mut().first_mut() {
+                        if let Some(dst) = data.data() {
+                            let n = dst.len().min(frame.pixels.len());
+                            dst[..n].copy_from_slice(&frame.pixels[..n]);
+                            let chunk = data.chunk_mut();
+                            *chunk.size_mut() = n as u32;
+                            *chunk.stride_mut() = (frame.width * 4) as i32;
+                        }
+                    }
+                }
+            })
+            .register()
+            .map_err(|e| format!("Failed to register PipeWire listener: {:?}", e))?;
+
+        stream
+            .connect(Direction::Output, None, StreamFlags::MAP_BUFFERS, &mut [format_pod])
+            .map_err(|e| format!("PipeWire stream connect failed: {:?}", e))?;
+
+        Ok((main_loop, stream))
+    })();
+
+    let (main_loop, stream) = match setup {
+        Ok(pair) => pair,
+        Err(e) => {
+            let _ = node_id_tx.send(Err(e));
+            return;
+        }
+    };
+
+    let _ = node_id_tx.send(Ok(stream.node_id()));
+
+    while !shutdown.load(Ordering::SeqCst) {
+        main_loop.loop_().iterate(Duration::from_millis(50));
+    }
+}
+
+/// Build the SPA `Format` pod PipeWire needs up front to negotiate a raw
+/// RGBA video stream at `width`x`height`, 30fps.
+fn build_video_format_pod(width: u32, height: u32) -> Result<Pod, String> {
+    let obj = pod::object! {
+        pod::sys::SPA_TYPE_OBJECT_Format,
+        pod::sys::SPA_PARAM_EnumFormat,
+        pod::property!(pod::sys::SPA_FORMAT_mediaType, Id, pod::sys::SPA_MEDIA_TYPE_video),
+        pod::property!(pod::sys::SPA_FORMAT_mediaSubtype, Id, pod::sys::SPA_MEDIA_SUBTYPE_raw),
+        pod::property!(pod::sys::SPA_FORMAT_VIDEO_format, Id, VideoFormat::RGBA),
+        pod::property!(pod::sys::SPA_FORMAT_VIDEO_size, Rectangle, Rectangle { width, height }),
+        pod::property!(pod::sys::SPA_FORMAT_VIDEO_framerate, Fraction, Fraction { num: 30, denom: 1 }),
+    };
+
+    let bytes = PodSerializer::serialize(Cursor::new(Vec::new()), &pod::Value::Object(obj))
+        .map(|(cursor, _)| cursor.into_inner())
+        .map_err(|e| format!("Failed to serialize SPA format pod: {:?}", e))?;
+
+    Pod::from_bytes(&bytes).ok_or_else(|| "Failed to build format Pod from serialized bytes".to_string())
+}
+
+/// Start publishing the current video as a PipeWire virtual camera, at
+/// the given frame size. Returns the new stream's PipeWire node id so a
+/// consumer (e.g. a conferencing app's camera picker) can target it.
+#[tauri::command]
+pub fn mpv_start_screencast(
+    width: u32,
+    height: u32,
+    state: State<Arc<ScreencastState>>,
+) -> Result<u32, String> {
+    state.start(width, height)
+}
+
+/// Stop the running screencast and tear down its PipeWire node.
+#[tauri::command]
+pub fn mpv_stop_screencast(state: State<Arc<ScreencastState>>) -> MpvResult {
+    state.stop();
+    MpvResult::ok()
+}
@@ -0,0 +1,380 @@
+//! Watch-party sync: lets several sbtlTV instances watch the same stream in
+//! lockstep over a plain TCP connection. One peer hosts (holds the
+//! authoritative source URL and position) and the rest join as clients;
+//! local mpv `pause`/`time-pos` changes are broadcast to peers and inbound
+//! commands are applied back through `ExternalMpv`. Not encrypted - this is
+//! a LAN/VPN convenience feature, not the p2p module's paired-device sync.
+
+use super::external::ExternalMpv;
+use super::{MpvResult, MpvState};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+/// How long a remote command we just applied locally is allowed to "echo"
+/// back through `handle_mpv_event` before it's treated as a genuine local
+/// change that should itself be broadcast.
+const ECHO_WINDOW: Duration = Duration::from_millis(500);
+const POSITION_EPSILON: f64 = 0.75;
+/// `time-pos` ticks roughly once a second during normal playback; only a
+/// jump bigger than this is treated as a deliberate seek worth broadcasting.
+const SEEK_JUMP_THRESHOLD: f64 = 2.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SyncMessage {
+    NewConnection { username: String },
+    Source { url: String },
+    Ready,
+    Play { time: f64 },
+    Pause { time: f64 },
+    Seek { time: f64 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SyncRole {
+    Host,
+    Client,
+}
+
+struct RemoteEcho {
+    value: f64,
+    at: Instant,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncPeerJoined {
+    username: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncDrift {
+    local: f64,
+    remote: f64,
+}
+
+/// Watch-party session state: connected peers, the authoritative source,
+/// and the echo-suppression marker that stops an applied remote command
+/// from being read back off mpv and re-broadcast.
+pub struct SyncState {
+    role: Mutex<Option<SyncRole>>,
+    peers: Mutex<HashMap<u64, UnboundedSender<SyncMessage>>>,
+    next_peer_id: AtomicU64,
+    source: Mutex<Option<String>>,
+    position: Mutex<f64>,
+    /// Keyed by property name so `Play`/`Pause` - which both note an echo
+    /// for "pause" and "time-pos" before applying - don't clobber each
+    /// other's marker. A clobbered marker means mpv's two asynchronous
+    /// property-change events can't both be recognized as echoes, and the
+    /// second one gets re-broadcast back to the peer that sent it,
+    /// producing an unbounded ping-pong loop.
+    last_remote: Mutex<HashMap<&'static str, RemoteEcho>>,
+}
+
+impl SyncState {
+    pub fn new() -> Self {
+        Self {
+            role: Mutex::new(None),
+            peers: Mutex::new(HashMap::new()),
+            next_peer_id: AtomicU64::new(1),
+            source: Mutex::new(None),
+            position: Mutex::new(0.0),
+            last_remote: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.role.lock().unwrap().is_some()
+    }
+
+    fn is_host(&self) -> bool {
+        matches!(*self.role.lock().unwrap(), Some(SyncRole::Host))
+    }
+
+    fn note_remote_applied(&self, property: &'static str, value: f64) {
+        self.last_remote
+            .lock()
+            .unwrap()
+            .insert(property, RemoteEcho { value, at: Instant::now() });
+    }
+
+    /// Returns true (and consumes the marker) if `value` looks like the
+    /// echo of a command we just applied on behalf of a peer.
+    fn take_if_echo(&self, property: &str, value: f64) -> bool {
+        let mut last = self.last_remote.lock().unwrap();
+        if let Some(echo) = last.get(property) {
+            if (echo.value - value).abs() < POSITION_EPSILON && echo.at.elapsed() < ECHO_WINDOW {
+                last.remove(property);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn broadcast(&self, msg: SyncMessage, except: Option<u64>) {
+        let peers = self.peers.lock().unwrap();
+        for (id, tx) in peers.iter() {
+            if Some(*id) != except {
+                let _ = tx.send(msg.clone());
+            }
+        }
+    }
+
+    fn reset(&self) {
+        *self.role.lock().unwrap() = None;
+        self.peers.lock().unwrap().clear();
+        *self.source.lock().unwrap() = None;
+    }
+}
+
+impl Default for SyncState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Called from `handle_mpv_event` for every local `pause`/`time-pos`
+/// property-change. Broadcasts the change to peers unless it's the echo of
+/// a command a peer just asked us to apply.
+pub(super) fn on_local_property_change(app: &AppHandle, name: &str, data: Option<&serde_json::Value>) {
+    let Some(sync) = app.try_state::<Arc<SyncState>>() else {
+        return;
+    };
+    if !sync.is_active() {
+        return;
+    }
+
+    match name {
+        "pause" => {
+            let Some(paused) = data.and_then(|v| v.as_bool()) else {
+                return;
+            };
+            if sync.take_if_echo("pause", if paused { 1.0 } else { 0.0 }) {
+                return;
+            }
+            let time = *sync.position.lock().unwrap();
+            let msg = if paused { SyncMessage::Pause { time } } else { SyncMessage::Play { time } };
+            sync.broadcast(msg, None);
+        }
+        "time-pos" => {
+            let Some(time) = data.and_then(|v| v.as_f64()) else {
+                return;
+            };
+            let jumped = {
+                let mut last = sync.position.lock().unwrap();
+                let jumped = (time - *last).abs() > SEEK_JUMP_THRESHOLD;
+                *last = time;
+                jumped
+            };
+            if !jumped || sync.take_if_echo("time-pos", time) {
+                return;
+            }
+            sync.broadcast(SyncMessage::Seek { time }, None);
+        }
+        _ => {}
+    }
+}
+
+fn apply_local(mpv: &tauri::State<'_, MpvState>, f: impl FnOnce(&ExternalMpv)) {
+    let guard = mpv.external.lock().unwrap();
+    if let Some(ext) = &guard.mpv {
+        f(ext);
+    }
+}
+
+fn local_position(mpv: &tauri::State<'_, MpvState>) -> Option<f64> {
+    let guard = mpv.external.lock().unwrap();
+    guard.mpv.as_ref().map(|ext| ext.get_status().position)
+}
+
+fn apply_inbound(app: &AppHandle, sync: &Arc<SyncState>, from_peer: u64, msg: SyncMessage) {
+    let Some(mpv) = app.try_state::<MpvState>() else {
+        return;
+    };
+
+    match msg {
+        SyncMessage::NewConnection { username } => {
+            if sync.is_host() {
+                let reply = {
+                    let source = sync.source.lock().unwrap().clone();
+                    let time = *sync.position.lock().unwrap();
+                    source.map(|url| (url, time))
+                };
+                if let Some((url, time)) = reply {
+                    let peers = sync.peers.lock().unwrap();
+                    if let Some(tx) = peers.get(&from_peer) {
+                        let _ = tx.send(SyncMessage::Source { url });
+                        let _ = tx.send(SyncMessage::Seek { time });
+                    }
+                }
+            }
+            let _ = app.emit("sync-peer-joined", SyncPeerJoined { username });
+        }
+        SyncMessage::Source { url } => {
+            *sync.source.lock().unwrap() = Some(url.clone());
+            apply_local(&mpv, |ext| {
+                let _ = ext.load(&url);
+            });
+        }
+        SyncMessage::Ready => {}
+        SyncMessage::Play { time } => {
+            sync.note_remote_applied("pause", 0.0);
+            sync.note_remote_applied("time-pos", time);
+            apply_local(&mpv, |ext| {
+                let _ = ext.seek(time);
+                let _ = ext.play();
+            });
+            sync.broadcast(SyncMessage::Play { time }, Some(from_peer));
+        }
+        SyncMessage::Pause { time } => {
+            sync.note_remote_applied("pause", 1.0);
+            sync.note_remote_applied("time-pos", time);
+            apply_local(&mpv, |ext| {
+                let _ = ext.seek(time);
+                let _ = ext.pause();
+            });
+            sync.broadcast(SyncMessage::Pause { time }, Some(from_peer));
+        }
+        SyncMessage::Seek { time } => {
+            sync.note_remote_applied("time-pos", time);
+            if let Some(local) = local_position(&mpv) {
+                if (local - time).abs() > POSITION_EPSILON {
+                    let _ = app.emit("sync-drift", SyncDrift { local, remote: time });
+                }
+            }
+            apply_local(&mpv, |ext| {
+                let _ = ext.seek(time);
+            });
+            sync.broadcast(SyncMessage::Seek { time }, Some(from_peer));
+        }
+    }
+}
+
+/// Owns one peer connection: pumps outbound messages from `sync.peers` onto
+/// the socket, and applies inbound messages as they arrive.
+async fn handle_peer(stream: TcpStream, username: String, app: AppHandle, sync: Arc<SyncState>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let (tx, mut rx) = mpsc::unbounded_channel::<SyncMessage>();
+
+    let peer_id = sync.next_peer_id.fetch_add(1, Ordering::SeqCst);
+    sync.peers.lock().unwrap().insert(peer_id, tx.clone());
+
+    let write_task = tauri::async_runtime::spawn(async move {
+        while let Some(msg) = rx.recv().await {
+            let Ok(json) = serde_json::to_string(&msg) else { continue };
+            if writer.write_all(format!("{}\n", json).as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    if !sync.is_host() {
+        let _ = tx.send(SyncMessage::NewConnection { username });
+    }
+
+    loop {
+        match lines.next_line().await {
+            Ok(Some(line)) if !line.trim().is_empty() => {
+                if let Ok(msg) = serde_json::from_str::<SyncMessage>(&line) {
+                    apply_inbound(&app, &sync, peer_id, msg);
+                }
+            }
+            Ok(Some(_)) => continue,
+            Ok(None) | Err(_) => break,
+        }
+    }
+
+    sync.peers.lock().unwrap().remove(&peer_id);
+    write_task.abort();
+}
+
+/// Host a watch-party session: binds a TCP listener and accepts peers for
+/// the lifetime of the app (or until `mpv_sync_leave` resets the role).
+/// `source` should be whatever the host already has loaded.
+#[tauri::command]
+pub async fn mpv_sync_host(
+    username: String,
+    port: u16,
+    source: String,
+    app: AppHandle,
+    sync: tauri::State<'_, Arc<SyncState>>,
+) -> Result<MpvResult, ()> {
+    let sync = sync.inner().clone();
+    let addr = format!("0.0.0.0:{}", port);
+
+    let listener = match TcpListener::bind(&addr).await {
+        Ok(l) => l,
+        Err(e) => return Ok(MpvResult::err(format!("Failed to bind {}: {}", addr, e))),
+    };
+
+    *sync.role.lock().unwrap() = Some(SyncRole::Host);
+    *sync.source.lock().unwrap() = Some(source);
+    log::info!("[SYNC] Hosting watch-party on {}", addr);
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer_addr)) => {
+                    log::info!("[SYNC] Peer connected: {}", peer_addr);
+                    tauri::async_runtime::spawn(handle_peer(
+                        stream,
+                        username.clone(),
+                        app.clone(),
+                        sync.clone(),
+                    ));
+                }
+                Err(e) => log::warn!("[SYNC] Accept failed: {}", e),
+            }
+        }
+    });
+
+    Ok(MpvResult::ok())
+}
+
+/// Join a watch-party session hosted at `host` (e.g. `"192.168.1.5:17998"`).
+#[tauri::command]
+pub async fn mpv_sync_join(
+    username: String,
+    host: String,
+    app: AppHandle,
+    sync: tauri::State<'_, Arc<SyncState>>,
+) -> Result<MpvResult, ()> {
+    let sync = sync.inner().clone();
+
+    let stream = match TcpStream::connect(&host).await {
+        Ok(s) => s,
+        Err(e) => return Ok(MpvResult::err(format!("Failed to connect to {}: {}", host, e))),
+    };
+
+    *sync.role.lock().unwrap() = Some(SyncRole::Client);
+    log::info!("[SYNC] Joined watch-party at {}", host);
+
+    tauri::async_runtime::spawn(handle_peer(stream, username, app, sync));
+
+    Ok(MpvResult::ok())
+}
+
+/// Update the authoritative source (called after the host loads new media)
+/// and push it out to every connected peer.
+#[tauri::command]
+pub fn mpv_sync_set_source(url: String, sync: tauri::State<'_, Arc<SyncState>>) -> MpvResult {
+    *sync.source.lock().unwrap() = Some(url.clone());
+    sync.broadcast(SyncMessage::Source { url }, None);
+    MpvResult::ok()
+}
+
+/// Leave the current session, dropping all peer connections.
+#[tauri::command]
+pub fn mpv_sync_leave(sync: tauri::State<'_, Arc<SyncState>>) -> MpvResult {
+    sync.reset();
+    MpvResult::ok()
+}
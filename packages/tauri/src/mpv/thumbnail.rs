@@ -0,0 +1,320 @@
+//! Seek-bar hover thumbnails, modeled on the thumbfast approach: a
+//! dedicated mpv instance, entirely separate from the playback instance,
+//! kept alive in idle mode so repeated requests are cheap and shut down
+//! after a period of no hovers. Requests coalesce onto a single-slot
+//! mailbox rather than a queue, so a burst of seek-bar hover events only
+//! ever renders the most recent one; a small cache keyed by quantized
+//! timestamp means scrubbing back and forth doesn't re-decode frames
+//! we've already produced.
+
+use super::error::MpvError;
+use super::external::find_mpv_binary;
+use super::ipc::MpvIpcClient;
+use super::MpvErrorPayload;
+use base64::Engine;
+use std::collections::{HashMap, VecDeque};
+use std::process::{Child, Command};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tokio::sync::{oneshot, Notify};
+
+/// How long the dedicated thumbnail instance is kept alive after its last
+/// request before being shut down, so an idle player doesn't pin an extra
+/// mpv process forever.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Rendered thumbnails are cached by (url, quantized time); at most this
+/// many are kept before the oldest is evicted.
+const CACHE_CAPACITY: usize = 32;
+/// Timestamps are bucketed to this granularity before cache lookup/seek -
+/// sub-second precision isn't visually distinguishable in a preview image.
+const TIME_QUANTUM: f64 = 1.0;
+
+fn quantize(time_secs: f64) -> i64 {
+    (time_secs / TIME_QUANTUM).round() as i64
+}
+
+fn thumb_socket_path() -> String {
+    let pid = std::process::id();
+    #[cfg(target_os = "windows")]
+    {
+        format!(r"\\.\pipe\mpv-thumb-socket-{}", pid)
+    }
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    {
+        format!("/tmp/mpv-thumb-socket-{}", pid)
+    }
+}
+
+/// The dedicated, headless mpv instance used only for rendering preview
+/// frames - never the user's primary playback instance, since seeking it
+/// would disrupt whatever is actually playing.
+struct ThumbnailMpv {
+    process: Child,
+    ipc: Arc<MpvIpcClient>,
+    loaded_url: Option<String>,
+}
+
+impl ThumbnailMpv {
+    fn spawn(app: &AppHandle) -> Result<Self, MpvError> {
+        let mpv_path = find_mpv_binary(app)?;
+        let socket_path = thumb_socket_path();
+
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        let _ = std::fs::remove_file(&socket_path);
+
+        let args = vec![
+            format!("--input-ipc-server={}", socket_path),
+            "--vo=null".to_string(),
+            "--no-audio".to_string(),
+            "--no-osc".to_string(),
+            "--osd-level=0".to_string(),
+            "--keep-open=yes".to_string(),
+            "--idle=yes".to_string(),
+            "--input-default-bindings=no".to_string(),
+            "--no-terminal".to_string(),
+            "--really-quiet".to_string(),
+            "--hr-seek=yes".to_string(),
+        ];
+
+        log::info!("[MPV-THUMB] Starting thumbnail instance: {}", socket_path);
+
+        let process = Command::new(&mpv_path)
+            .args(&args)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(MpvError::SpawnFailed)?;
+
+        std::thread::sleep(Duration::from_millis(300));
+
+        let ipc = Arc::new(MpvIpcClient::connect(&socket_path)?);
+
+        Ok(Self { process, ipc, loaded_url: None })
+    }
+
+    fn ensure_loaded(&mut self, url: &str) -> Result<(), MpvError> {
+        if self.loaded_url.as_deref() == Some(url) {
+            return Ok(());
+        }
+        self.ipc.send_command(&["loadfile", url])?;
+        self.loaded_url = Some(url.to_string());
+        // `loadfile`'s reply only confirms mpv accepted the command, not
+        // that the stream is demuxed and seekable yet - give it a moment.
+        std::thread::sleep(Duration::from_millis(200));
+        Ok(())
+    }
+
+    /// Seek to `time_secs` (clamped into the file's duration), scale the
+    /// frame to fit within `max_w`/`max_h` preserving aspect, and write it
+    /// out as a JPEG at `out_path`.
+    fn render_to_file(
+        &mut self,
+        url: &str,
+        time_secs: f64,
+        max_w: u32,
+        max_h: u32,
+        out_path: &str,
+    ) -> Result<(), MpvError> {
+        self.ensure_loaded(url)?;
+
+        let duration = self.ipc.get_property("duration")?.and_then(|v| v.as_f64());
+        let clamped = match duration {
+            Some(d) if d > 0.0 => time_secs.clamp(0.0, d),
+            _ => time_secs.max(0.0),
+        };
+
+        // `absolute+exact` forces a precise (non-keyframe) seek so the
+        // preview actually matches the requested time.
+        self.ipc.send_command(&["seek", &clamped.to_string(), "absolute+exact"])?;
+
+        let filter = format!("scale=w={}:h={}:force_original_aspect_ratio=decrease", max_w, max_h);
+        self.ipc.send_command(&["vf", "set", &filter])?;
+
+        self.ipc.send_command(&["screenshot-to-file", out_path, "video"])?;
+        Ok(())
+    }
+}
+
+impl Drop for ThumbnailMpv {
+    fn drop(&mut self) {
+        log::info!("[MPV-THUMB] Shutting down idle thumbnail instance");
+        let _ = self.ipc.send_command_async(&["quit"]);
+        let _ = self.process.kill();
+
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        {
+            let _ = std::fs::remove_file(&thumb_socket_path());
+        }
+    }
+}
+
+/// Small fixed-capacity cache of already-rendered thumbnails, keyed by the
+/// source url and quantized timestamp.
+struct ThumbnailCache {
+    entries: HashMap<(String, i64), Vec<u8>>,
+    order: VecDeque<(String, i64)>,
+}
+
+impl ThumbnailCache {
+    fn new() -> Self {
+        Self { entries: HashMap::new(), order: VecDeque::new() }
+    }
+
+    fn get(&self, key: &(String, i64)) -> Option<Vec<u8>> {
+        self.entries.get(key).cloned()
+    }
+
+    fn insert(&mut self, key: (String, i64), jpeg: Vec<u8>) {
+        if !self.entries.contains_key(&key) {
+            self.order.push_back(key.clone());
+            if self.order.len() > CACHE_CAPACITY {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.entries.remove(&oldest);
+                }
+            }
+        }
+        self.entries.insert(key, jpeg);
+    }
+}
+
+/// One pending hover request. Dropping the `reply` sender (by overwriting
+/// this request in `ThumbnailState::slot` before it's picked up) is how a
+/// stale request gets superseded: the original caller's `oneshot::Receiver`
+/// simply errors out.
+struct PendingRequest {
+    url: String,
+    time_secs: f64,
+    max_w: u32,
+    max_h: u32,
+    reply: oneshot::Sender<Result<Vec<u8>, MpvError>>,
+}
+
+/// Thumbnail subsystem state: the single-slot request mailbox, the
+/// rendered-frame cache, and the dedicated mpv instance (spawned lazily on
+/// first request, reaped after `IDLE_TIMEOUT`).
+pub struct ThumbnailState {
+    slot: Mutex<Option<PendingRequest>>,
+    notify: Notify,
+    worker_spawned: AtomicBool,
+    mpv: tokio::sync::Mutex<Option<ThumbnailMpv>>,
+    last_used: Mutex<Instant>,
+    cache: Mutex<ThumbnailCache>,
+}
+
+impl ThumbnailState {
+    pub fn new() -> Self {
+        Self {
+            slot: Mutex::new(None),
+            notify: Notify::new(),
+            worker_spawned: AtomicBool::new(false),
+            mpv: tokio::sync::Mutex::new(None),
+            last_used: Mutex::new(Instant::now()),
+            cache: Mutex::new(ThumbnailCache::new()),
+        }
+    }
+}
+
+impl Default for ThumbnailState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn render_thumbnail(
+    state: &ThumbnailState,
+    app: &AppHandle,
+    req: &PendingRequest,
+) -> Result<Vec<u8>, MpvError> {
+    *state.last_used.lock().unwrap() = Instant::now();
+
+    let mut guard = state.mpv.lock().await;
+    if guard.is_none() {
+        *guard = Some(ThumbnailMpv::spawn(app)?);
+    }
+    let mpv = guard.as_mut().expect("just spawned above");
+
+    let out_path = std::env::temp_dir().join(format!("sbtltv-thumb-{}.jpg", std::process::id()));
+    let out_path_str = out_path.to_string_lossy().to_string();
+
+    let render_result = mpv.render_to_file(&req.url, req.time_secs, req.max_w, req.max_h, &out_path_str);
+    drop(guard);
+
+    render_result?;
+
+    let jpeg = std::fs::read(&out_path)
+        .map_err(|e| MpvError::ConnectFailed(format!("Failed to read rendered thumbnail: {}", e)))?;
+    let _ = std::fs::remove_file(&out_path);
+
+    Ok(jpeg)
+}
+
+/// Spawn the (one-time) worker loop that drains `state.slot` and the
+/// reaper loop that shuts down an idle thumbnail instance. Safe to call on
+/// every request - only the first call actually spawns anything.
+fn ensure_background_tasks(state: Arc<ThumbnailState>, app: AppHandle) {
+    if state.worker_spawned.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let reaper_state = state.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(IDLE_TIMEOUT / 2).await;
+            let idle_for = reaper_state.last_used.lock().unwrap().elapsed();
+            if idle_for >= IDLE_TIMEOUT {
+                reaper_state.mpv.lock().await.take(); // Drop closes the process.
+            }
+        }
+    });
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            state.notify.notified().await;
+            // Keep draining in case a newer request races in while we're
+            // rendering the one we just took.
+            while let Some(req) = state.slot.lock().unwrap().take() {
+                let result = render_thumbnail(&state, &app, &req).await;
+                if let Ok(jpeg) = &result {
+                    state.cache.lock().unwrap().insert((req.url.clone(), quantize(req.time_secs)), jpeg.clone());
+                }
+                let _ = req.reply.send(result);
+            }
+        }
+    });
+}
+
+/// Render (or fetch from cache) a preview frame at `time_secs` into the
+/// url's video, downscaled to fit within `max_w`/`max_h`. Returns the
+/// frame as a base64-encoded JPEG.
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+#[tauri::command]
+pub async fn mpv_thumbnail_at(
+    url: String,
+    time_secs: f64,
+    max_w: u32,
+    max_h: u32,
+    app: AppHandle,
+    state: tauri::State<'_, Arc<ThumbnailState>>,
+) -> Result<String, MpvErrorPayload> {
+    let state = state.inner().clone();
+    let key = (url.clone(), quantize(time_secs));
+
+    if let Some(jpeg) = state.cache.lock().unwrap().get(&key) {
+        return Ok(base64::engine::general_purpose::STANDARD.encode(&jpeg));
+    }
+
+    ensure_background_tasks(state.clone(), app);
+
+    let (tx, rx) = oneshot::channel();
+    *state.slot.lock().unwrap() = Some(PendingRequest { url, time_secs, max_w, max_h, reply: tx });
+    state.notify.notify_one();
+
+    match rx.await {
+        Ok(Ok(jpeg)) => Ok(base64::engine::general_purpose::STANDARD.encode(&jpeg)),
+        Ok(Err(e)) => Err(e.into()),
+        Err(_) => Err(MpvError::ConnectFailed("Superseded by a newer thumbnail request".to_string()).into()),
+    }
+}
@@ -0,0 +1,270 @@
+//! GPU-side RGBA→YUV420 color conversion.
+//!
+//! Renders the BT.709 conversion as three fragment-shader passes into
+//! R8 textures (full-res Y, half-res U, half-res V) instead of the
+//! per-pixel CPU loop in `OffscreenRenderer::read_as_yuv420`. The vertical
+//! flip mpv's readback needs is folded into the texture coordinates rather
+//! than done with an extra pass.
+//!
+//! Feature-gated: software-GL / headless environments without a usable
+//! GLSL compiler should keep using the CPU path.
+
+use gl::types::*;
+use std::ffi::CString;
+
+const VERTEX_SHADER: &str = r#"#version 330 core
+layout(location = 0) in vec2 pos;
+out vec2 v_uv;
+void main() {
+    // Fold mpv's bottom-up readback into the texture coordinate flip.
+    v_uv = vec2((pos.x + 1.0) * 0.5, 1.0 - (pos.y + 1.0) * 0.5);
+    gl_Position = vec4(pos, 0.0, 1.0);
+}
+"#;
+
+// Fragment shaders take the RGB->Y/U/V row of the active color matrix as a
+// uniform vec4(coefficients, offset) so BT.601/709/2020 share one program.
+const Y_FRAGMENT_SHADER: &str = r#"#version 330 core
+in vec2 v_uv;
+out float frag_y;
+uniform sampler2D u_source;
+uniform vec4 u_coeffs; // r, g, b, offset
+void main() {
+    vec3 rgb = texture(u_source, v_uv).rgb * 255.0;
+    frag_y = (dot(rgb, u_coeffs.rgb) + u_coeffs.a) / 255.0;
+}
+"#;
+
+const CHROMA_FRAGMENT_SHADER: &str = r#"#version 330 core
+in vec2 v_uv;
+out float frag_chroma;
+uniform sampler2D u_source;
+uniform vec4 u_coeffs; // r, g, b, offset
+void main() {
+    // Sample at the top-left pixel of each 2x2 block (nearest-neighbor
+    // subsampling), matching the CPU path's block selection.
+    vec3 rgb = texture(u_source, v_uv).rgb * 255.0;
+    frag_chroma = (dot(rgb, u_coeffs.rgb) + u_coeffs.a) / 255.0;
+}
+"#;
+
+unsafe fn compile_shader(kind: GLenum, src: &str) -> Result<GLuint, String> {
+    let shader = gl::CreateShader(kind);
+    let c_src = CString::new(src).unwrap();
+    gl::ShaderSource(shader, 1, &c_src.as_ptr(), std::ptr::null());
+    gl::CompileShader(shader);
+
+    let mut status = gl::FALSE as GLint;
+    gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut status);
+    if status != gl::TRUE as GLint {
+        let mut len = 0;
+        gl::GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut len);
+        let mut buf = vec![0u8; len as usize];
+        gl::GetShaderInfoLog(shader, len, std::ptr::null_mut(), buf.as_mut_ptr() as *mut _);
+        return Err(String::from_utf8_lossy(&buf).to_string());
+    }
+    Ok(shader)
+}
+
+unsafe fn link_program(vert: GLuint, frag: GLuint) -> Result<GLuint, String> {
+    let program = gl::CreateProgram();
+    gl::AttachShader(program, vert);
+    gl::AttachShader(program, frag);
+    gl::LinkProgram(program);
+
+    let mut status = gl::FALSE as GLint;
+    gl::GetProgramiv(program, gl::LINK_STATUS, &mut status);
+    if status != gl::TRUE as GLint {
+        let mut len = 0;
+        gl::GetProgramiv(program, gl::INFO_LOG_LENGTH, &mut len);
+        let mut buf = vec![0u8; len as usize];
+        gl::GetProgramInfoLog(program, len, std::ptr::null_mut(), buf.as_mut_ptr() as *mut _);
+        return Err(String::from_utf8_lossy(&buf).to_string());
+    }
+    Ok(program)
+}
+
+/// A single render-to-texture target for one YUV plane.
+struct PlaneTarget {
+    fbo: GLuint,
+    texture: GLuint,
+    width: u32,
+    height: u32,
+}
+
+impl PlaneTarget {
+    unsafe fn new(width: u32, height: u32) -> Self {
+        let mut fbo = 0;
+        let mut texture = 0;
+        gl::GenFramebuffers(1, &mut fbo);
+        gl::GenTextures(1, &mut texture);
+
+        gl::BindTexture(gl::TEXTURE_2D, texture);
+        gl::TexImage2D(
+            gl::TEXTURE_2D,
+            0,
+            gl::R8 as GLint,
+            width as GLsizei,
+            height as GLsizei,
+            0,
+            gl::RED,
+            gl::UNSIGNED_BYTE,
+            std::ptr::null(),
+        );
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as GLint);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as GLint);
+
+        gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+        gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, texture, 0);
+        gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+
+        Self { fbo, texture, width, height }
+    }
+
+    unsafe fn resize(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+        gl::BindTexture(gl::TEXTURE_2D, self.texture);
+        gl::TexImage2D(
+            gl::TEXTURE_2D,
+            0,
+            gl::R8 as GLint,
+            width as GLsizei,
+            height as GLsizei,
+            0,
+            gl::RED,
+            gl::UNSIGNED_BYTE,
+            std::ptr::null(),
+        );
+    }
+}
+
+impl Drop for PlaneTarget {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.fbo);
+            gl::DeleteTextures(1, &self.texture);
+        }
+    }
+}
+
+/// Renders RGBA→YUV420 conversion on the GPU: a full-resolution Y plane
+/// and two half-resolution U/V planes, each an R8 texture.
+pub struct GpuYuvConverter {
+    quad_vao: GLuint,
+    quad_vbo: GLuint,
+    y_program: GLuint,
+    chroma_program: GLuint,
+    y_target: PlaneTarget,
+    u_target: PlaneTarget,
+    v_target: PlaneTarget,
+}
+
+impl GpuYuvConverter {
+    pub fn new(width: u32, height: u32) -> Result<Self, String> {
+        unsafe {
+            let vert = compile_shader(gl::VERTEX_SHADER, VERTEX_SHADER)?;
+            let y_frag = compile_shader(gl::FRAGMENT_SHADER, Y_FRAGMENT_SHADER)?;
+            let chroma_frag = compile_shader(gl::FRAGMENT_SHADER, CHROMA_FRAGMENT_SHADER)?;
+
+            let y_program = link_program(vert, y_frag)?;
+            let chroma_program = link_program(vert, chroma_frag)?;
+            gl::DeleteShader(vert);
+            gl::DeleteShader(y_frag);
+            gl::DeleteShader(chroma_frag);
+
+            // Fullscreen triangle strip covering clip space.
+            let quad: [f32; 8] = [-1.0, -1.0, 1.0, -1.0, -1.0, 1.0, 1.0, 1.0];
+            let mut quad_vao = 0;
+            let mut quad_vbo = 0;
+            gl::GenVertexArrays(1, &mut quad_vao);
+            gl::GenBuffers(1, &mut quad_vbo);
+            gl::BindVertexArray(quad_vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, quad_vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                std::mem::size_of_val(&quad) as isize,
+                quad.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+            gl::VertexAttribPointer(0, 2, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+            gl::EnableVertexAttribArray(0);
+            gl::BindVertexArray(0);
+
+            let half_w = (width / 2).max(1);
+            let half_h = (height / 2).max(1);
+
+            Ok(Self {
+                quad_vao,
+                quad_vbo,
+                y_program,
+                chroma_program,
+                y_target: PlaneTarget::new(width, height),
+                u_target: PlaneTarget::new(half_w, half_h),
+                v_target: PlaneTarget::new(half_w, half_h),
+            })
+        }
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let half_w = (width / 2).max(1);
+        let half_h = (height / 2).max(1);
+        unsafe {
+            self.y_target.resize(width, height);
+            self.u_target.resize(half_w, half_h);
+            self.v_target.resize(half_w, half_h);
+        }
+    }
+
+    /// Render `source_texture` (the FBO's RGBA color attachment) through the
+    /// Y/U/V passes, returning the three plane texture ids. Each plane gets
+    /// its own RGB->component coefficient row (+offset) from the active
+    /// color matrix. Caller reads the textures back (ideally via PBOs)
+    /// into `Vec<u8>` planes.
+    pub fn convert(
+        &mut self,
+        source_texture: GLuint,
+        y_coeffs: [f32; 4],
+        u_coeffs: [f32; 4],
+        v_coeffs: [f32; 4],
+    ) -> (GLuint, GLuint, GLuint) {
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, source_texture);
+            gl::BindVertexArray(self.quad_vao);
+
+            self.render_plane(self.y_program, &self.y_target, y_coeffs);
+            self.render_plane(self.chroma_program, &self.u_target, u_coeffs);
+            self.render_plane(self.chroma_program, &self.v_target, v_coeffs);
+
+            gl::BindVertexArray(0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+
+        (self.y_target.texture, self.u_target.texture, self.v_target.texture)
+    }
+
+    unsafe fn render_plane(&self, program: GLuint, target: &PlaneTarget, coeffs: [f32; 4]) {
+        gl::BindFramebuffer(gl::FRAMEBUFFER, target.fbo);
+        gl::Viewport(0, 0, target.width as GLsizei, target.height as GLsizei);
+        gl::UseProgram(program);
+
+        let source_loc = gl::GetUniformLocation(program, CString::new("u_source").unwrap().as_ptr());
+        gl::Uniform1i(source_loc, 0);
+        let coeffs_loc = gl::GetUniformLocation(program, CString::new("u_coeffs").unwrap().as_ptr());
+        gl::Uniform4f(coeffs_loc, coeffs[0], coeffs[1], coeffs[2], coeffs[3]);
+
+        gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
+    }
+}
+
+impl Drop for GpuYuvConverter {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.quad_vao);
+            gl::DeleteBuffers(1, &self.quad_vbo);
+            gl::DeleteProgram(self.y_program);
+            gl::DeleteProgram(self.chroma_program);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! Per-device X25519 identity used to authenticate the pairing handshake
+//! and the Noise tunnel. Stored once, reused across restarts, the same
+//! way a Xtream password lives in the keyring rather than the JSON config.
+//!
+//! This is deliberately a X25519 key, not an Ed25519 one: it's the static
+//! key Noise_XX itself Diffie-Hellmans against, so it has to be the same
+//! curve the tunnel negotiates on, not a signing key reused for a purpose
+//! it wasn't built for.
+
+use crate::storage::{get_secret, set_secret};
+use rand::rngs::OsRng;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+pub type PublicKeyHex = String;
+
+pub struct DeviceIdentity {
+    static_secret: StaticSecret,
+}
+
+impl DeviceIdentity {
+    pub fn public_key_hex(&self) -> PublicKeyHex {
+        hex::encode(PublicKey::from(&self.static_secret).to_bytes())
+    }
+
+    /// The raw private scalar, for `transport::sync_over_tunnel` to hand
+    /// to the Noise builder as `local_private_key`.
+    pub fn private_key_bytes(&self) -> [u8; 32] {
+        self.static_secret.to_bytes()
+    }
+}
+
+/// Load the persisted identity from the keyring, or generate and persist
+/// a new one on first launch.
+pub fn load_or_create(secret_key: &str) -> Result<DeviceIdentity, String> {
+    if let Some(stored) = get_secret(secret_key) {
+        let bytes = hex::decode(&stored).map_err(|e| format!("corrupt device identity: {}", e))?;
+        let array: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| "corrupt device identity: wrong length".to_string())?;
+        return Ok(DeviceIdentity { static_secret: StaticSecret::from(array) });
+    }
+
+    let static_secret = StaticSecret::random_from_rng(OsRng);
+    set_secret(secret_key, &hex::encode(static_secret.to_bytes()))?;
+    Ok(DeviceIdentity { static_secret })
+}
@@ -0,0 +1,358 @@
+//! Peer-to-peer sync of sources and settings across a user's own devices.
+//!
+//! No central server: two installs pair directly (short pairing code +
+//! Noise-authenticated tunnel), then reconcile `StoreData` by last-write-wins
+//! per field using a monotonically increasing revision counter. Secrets
+//! (Xtream passwords, TMDB/RPDB keys) travel only inside the encrypted
+//! tunnel and are written straight into the OS keyring on the receiving
+//! side - they never touch the plaintext JSON config.
+
+mod identity;
+mod pairing;
+mod transport;
+
+pub use identity::DeviceIdentity;
+pub use pairing::PairingCode;
+
+use crate::storage::{keyring_key, set_secret, StorageState};
+use identity::PublicKeyHex;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+/// Info exchanged right after the Noise handshake, before any sync data.
+/// Lets each side log/display who it's talking to and detect a config
+/// version mismatch before attempting to reconcile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeInformation {
+    pub device_name: String,
+    pub platform: String,
+    pub config_revision: u64,
+    pub public_key: String,
+}
+
+/// A single field-level change, used for last-write-wins reconciliation.
+/// `revision` is the sending device's global counter at the time the
+/// field was last written, so a later write always wins regardless of
+/// wall-clock skew between devices.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncedField {
+    pub path: String,
+    pub value: serde_json::Value,
+    pub revision: u64,
+}
+
+/// Secrets are serialized separately from `SyncedField`s so they can be
+/// kept out of any log/diagnostic dump of the sync payload and routed
+/// straight into the keyring on receipt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncedSecret {
+    pub keyring_key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncPayload {
+    pub node: NodeInformation,
+    pub fields: Vec<SyncedField>,
+    pub secrets: Vec<SyncedSecret>,
+}
+
+pub struct P2pState {
+    identity: DeviceIdentity,
+    /// Public keys of devices this install has paired with, trusted to
+    /// open a tunnel without going through pairing again.
+    trusted_peers: Mutex<Vec<PublicKeyHex>>,
+    /// The inbound-sync accept loop started by `p2p_start_listening`, so a
+    /// paired peer can reach this device instead of only ever being able
+    /// to dial out itself.
+    listener_task: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+}
+
+impl P2pState {
+    fn is_trusted(&self, key: &PublicKeyHex) -> bool {
+        self.trusted_peers.lock().unwrap().contains(key)
+    }
+
+    fn is_listening(&self) -> bool {
+        self.listener_task.lock().unwrap().is_some()
+    }
+
+    fn stop_listening(&self) {
+        if let Some(task) = self.listener_task.lock().unwrap().take() {
+            task.abort();
+        }
+    }
+}
+
+const SECRET_KEY_IDENTITY: &str = "p2p:device-identity";
+
+pub fn init(app: &tauri::App) -> Result<(), String> {
+    let identity = identity::load_or_create(SECRET_KEY_IDENTITY)?;
+
+    // Pairings persisted across restarts - see `StoreData::trusted_peers`
+    // and `persist_trusted_peer` below - otherwise every restart forgets
+    // every pairing and refuses to sync with devices the user already
+    // trusted.
+    let trusted_peers = app
+        .state::<StorageState>()
+        .data
+        .lock()
+        .map(|d| d.trusted_peers())
+        .unwrap_or_default();
+
+    app.manage(Arc::new(P2pState {
+        identity,
+        trusted_peers: Mutex::new(trusted_peers),
+        listener_task: Mutex::new(None),
+    }));
+    Ok(())
+}
+
+/// Persist a newly-trusted peer so it survives a restart, and add it to
+/// the in-memory set used for the current session's trust checks.
+fn persist_trusted_peer(app: &AppHandle, state: &P2pState, peer_key: PublicKeyHex) -> Result<(), String> {
+    {
+        let mut peers = state.trusted_peers.lock().map_err(|e| e.to_string())?;
+        if !peers.contains(&peer_key) {
+            peers.push(peer_key.clone());
+        }
+    }
+
+    let storage = app.state::<StorageState>();
+    {
+        let mut data = storage.data.lock().map_err(|e| e.to_string())?;
+        data.add_trusted_peer(peer_key);
+    }
+    storage.save()
+}
+
+#[tauri::command]
+pub fn p2p_device_info(app: AppHandle, state: tauri::State<Arc<P2pState>>) -> NodeInformation {
+    let storage = app.state::<StorageState>();
+    let revision = storage
+        .data
+        .lock()
+        .map(|d| d.revision)
+        .unwrap_or(0);
+
+    NodeInformation {
+        device_name: device_name(),
+        platform: platform_string().to_string(),
+        config_revision: revision,
+        public_key: state.identity.public_key_hex(),
+    }
+}
+
+/// Generate a short pairing code the other device's user types in (or
+/// scans as a QR), encoding this device's public key and a one-time
+/// rendezvous token so the two installs can find each other and
+/// authenticate the Noise handshake.
+#[tauri::command]
+pub fn p2p_generate_pairing_code(state: tauri::State<Arc<P2pState>>) -> PairingCode {
+    pairing::generate(&state.identity)
+}
+
+/// Complete pairing using a code shown on the other device. On success,
+/// the peer's public key is trusted for future sync tunnels without
+/// re-pairing.
+#[tauri::command]
+pub fn p2p_pair_with_code(
+    redeem_token: String,
+    app: AppHandle,
+    state: tauri::State<Arc<P2pState>>,
+) -> Result<(), String> {
+    let peer_key = pairing::redeem(&redeem_token, &state.identity)?;
+    persist_trusted_peer(&app, &state, peer_key)
+}
+
+/// Open a tunnel to a paired peer at `addr`, exchange `NodeInformation`,
+/// then push/pull field-level changes and reconcile by last-write-wins.
+/// Refuses to exchange anything - including `SyncPayload.secrets` - unless
+/// the peer's Noise static key matches one added via `p2p_pair_with_code`.
+#[tauri::command]
+pub async fn p2p_sync_now(
+    addr: String,
+    app: AppHandle,
+    state: tauri::State<'_, Arc<P2pState>>,
+) -> Result<(), String> {
+    let local_payload = build_sync_payload(&app)?;
+    let state = state.inner().clone();
+
+    let remote_payload = transport::sync_over_tunnel(&addr, &state.identity, local_payload, {
+        let state = state.clone();
+        move |key: &PublicKeyHex| state.is_trusted(key)
+    })
+    .await?;
+
+    apply_sync_payload(&app, remote_payload)
+}
+
+/// Start accepting inbound sync connections on `port`, so a peer that
+/// paired with this device can dial in instead of this device always
+/// having to be the one to initiate. Runs until `p2p_stop_listening` or
+/// the app shuts down.
+#[tauri::command]
+pub async fn p2p_start_listening(
+    port: u16,
+    app: AppHandle,
+    state: tauri::State<'_, Arc<P2pState>>,
+) -> Result<(), String> {
+    let state = state.inner().clone();
+    if state.is_listening() {
+        return Err("P2P listener already running".to_string());
+    }
+
+    let addr = format!("0.0.0.0:{}", port);
+    let listen_state = state.clone();
+    let listen_app = app.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        let identity = &listen_state.identity;
+        let build_local_payload = {
+            let app = listen_app.clone();
+            move || build_sync_payload(&app)
+        };
+        let is_trusted = {
+            let state = listen_state.clone();
+            move |key: &PublicKeyHex| state.is_trusted(key)
+        };
+        let on_peer_payload = {
+            let app = listen_app.clone();
+            move |payload: SyncPayload| apply_sync_payload(&app, payload)
+        };
+
+        if let Err(e) =
+            transport::listen(&addr, identity, build_local_payload, is_trusted, on_peer_payload).await
+        {
+            log::warn!("[P2P] Listener stopped: {}", e);
+        }
+    });
+
+    *state.listener_task.lock().map_err(|e| e.to_string())? = Some(task);
+    Ok(())
+}
+
+/// Stop accepting inbound sync connections.
+#[tauri::command]
+pub fn p2p_stop_listening(state: tauri::State<Arc<P2pState>>) -> Result<(), String> {
+    state.stop_listening();
+    Ok(())
+}
+
+fn device_name() -> String {
+    hostname::get()
+        .ok()
+        .and_then(|h| h.into_string().ok())
+        .unwrap_or_else(|| "sbtlTV device".to_string())
+}
+
+/// Mirrors `platform::get_platform` but as a single string, for embedding
+/// in `NodeInformation` rather than the frontend-facing struct shape.
+fn platform_string() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "macos") {
+        "macos"
+    } else {
+        "linux"
+    }
+}
+
+/// Snapshot the locally-writable fields (sources + settings) plus their
+/// secrets into a payload ready to send over the tunnel.
+fn build_sync_payload(app: &AppHandle) -> Result<SyncPayload, String> {
+    let storage = app.state::<StorageState>();
+    let data = storage.data.lock().map_err(|e| e.to_string())?;
+
+    let fields = vec![
+        SyncedField {
+            path: "settings".to_string(),
+            value: serde_json::to_value(&data.settings).map_err(|e| e.to_string())?,
+            revision: data.revision,
+        },
+        SyncedField {
+            path: "sources".to_string(),
+            value: serde_json::to_value(data.synced_sources()).map_err(|e| e.to_string())?,
+            revision: data.revision,
+        },
+    ];
+
+    let mut secrets = Vec::new();
+    for source in data.source_identities() {
+        let key = keyring_key(&source.id, "password");
+        if let Some(value) = crate::storage::get_secret(&key) {
+            secrets.push(SyncedSecret { keyring_key: key, value });
+        }
+    }
+
+    Ok(SyncPayload {
+        node: NodeInformation {
+            device_name: device_name(),
+            platform: platform_string().to_string(),
+            config_revision: data.revision,
+            public_key: String::new(),
+        },
+        fields,
+        secrets,
+    })
+}
+
+/// Merge an incoming payload: each field wins if its revision is strictly
+/// newer than our local one, and secrets land directly in the keyring.
+fn apply_sync_payload(app: &AppHandle, payload: SyncPayload) -> Result<(), String> {
+    let storage = app.state::<StorageState>();
+    let mut data = storage.data.lock().map_err(|e| e.to_string())?;
+
+    for field in payload.fields {
+        if field.revision <= data.revision {
+            continue; // local copy is newer or equal - keep it (LWW)
+        }
+        match field.path.as_str() {
+            "settings" => {
+                if let Ok(settings) = serde_json::from_value(field.value) {
+                    data.settings = settings;
+                }
+            }
+            "sources" => {
+                if let Ok(sources) = serde_json::from_value(field.value) {
+                    data.merge_sources(sources);
+                }
+            }
+            _ => {}
+        }
+        data.revision = data.revision.max(field.revision);
+    }
+
+    // A peer is trusted to sync its own sources/settings, not to overwrite
+    // arbitrary keyring entries - refuse anything outside the keys this
+    // device actually expects to receive (in particular, never this
+    // device's own `SECRET_KEY_IDENTITY`, which a compromised-but-trusted
+    // peer could otherwise use to hijack our Noise identity).
+    let expected_keys = expected_secret_keys(&data);
+    for secret in payload.secrets {
+        if !expected_keys.contains(&secret.keyring_key) {
+            log::warn!("[P2P] Refusing unexpected secret key from peer: {}", secret.keyring_key);
+            continue;
+        }
+        let _ = set_secret(&secret.keyring_key, &secret.value);
+    }
+
+    drop(data);
+    storage.save()
+}
+
+/// The keyring keys a peer is allowed to write via `SyncPayload::secrets`:
+/// each known source's password, plus the two settings-level API keys
+/// `build_sync_payload` itself never sends today but that a newer peer
+/// might. Anything else - most importantly `SECRET_KEY_IDENTITY` - is
+/// refused regardless of what a peer sends.
+fn expected_secret_keys(data: &crate::storage::StoreData) -> std::collections::HashSet<String> {
+    let mut keys: std::collections::HashSet<String> = data
+        .source_identities()
+        .into_iter()
+        .map(|s| keyring_key(&s.id, "password"))
+        .collect();
+    keys.insert("settings:tmdbApiKey".to_string());
+    keys.insert("settings:posterDbApiKey".to_string());
+    keys
+}
@@ -0,0 +1,45 @@
+//! Short pairing codes that let a user link two of their own devices
+//! without a central directory: one device shows a code (or its QR
+//! encoding), the other types/scans it to learn the first device's
+//! public key and a rendezvous address.
+
+use super::identity::{DeviceIdentity, PublicKeyHex};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// `display_code` is the short digit sequence shown to the user;
+/// `redeem_token` is the full encoded payload (public key + nonce),
+/// carried in the QR code the frontend renders alongside the digits so
+/// `redeem` below can recover the public key without a directory lookup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairingCode {
+    pub display_code: String,
+    pub redeem_token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PairingPayload {
+    public_key: PublicKeyHex,
+    nonce: u32,
+}
+
+pub fn generate(identity: &DeviceIdentity) -> PairingCode {
+    let nonce: u32 = rand::thread_rng().gen_range(0..1_000_000);
+    let payload = PairingPayload { public_key: identity.public_key_hex(), nonce };
+    let encoded = serde_json::to_vec(&payload).expect("pairing payload is always serializable");
+
+    PairingCode {
+        display_code: format!("{:06}", nonce),
+        redeem_token: hex::encode(encoded),
+    }
+}
+
+/// Redeem a token from the other device's `PairingCode::redeem_token`,
+/// returning its public key so it can be added to this device's
+/// trusted-peers list.
+pub fn redeem(redeem_token: &str, _identity: &DeviceIdentity) -> Result<PublicKeyHex, String> {
+    let bytes = hex::decode(redeem_token).map_err(|_| "Invalid pairing code".to_string())?;
+    let payload: PairingPayload =
+        serde_json::from_slice(&bytes).map_err(|_| "Invalid pairing code".to_string())?;
+    Ok(payload.public_key)
+}
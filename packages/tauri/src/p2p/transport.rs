@@ -0,0 +1,194 @@
+//! The encrypted tunnel sync runs over: a Noise_XX handshake (authenticated
+//! by each device's X25519 identity) wrapping a plain TCP stream, so
+//! `SyncPayload`s - including secrets - are never sent in the clear.
+//!
+//! Both sides of the pairing relationship are implemented here: the
+//! initiator (`sync_over_tunnel`, dials out to a known peer address) and
+//! the responder (`listen`, accepts an inbound connection from a peer that
+//! dials *this* device). Either device can start a sync.
+
+use super::identity::{DeviceIdentity, PublicKeyHex};
+use super::SyncPayload;
+use snow::Builder;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const NOISE_PATTERN: &str = "Noise_XX_25519_ChaChaPoly_BLAKE2s";
+
+/// Connect to `addr`, perform the Noise_XX handshake as the initiator,
+/// verify the peer against `is_trusted`, and exchange `SyncPayload`s: send
+/// ours, receive theirs. Returns the peer's payload for the caller to
+/// reconcile against local state.
+pub async fn sync_over_tunnel(
+    addr: &str,
+    identity: &DeviceIdentity,
+    local_payload: SyncPayload,
+    is_trusted: impl Fn(&PublicKeyHex) -> bool,
+) -> Result<SyncPayload, String> {
+    let mut stream = TcpStream::connect(addr).await.map_err(|e| e.to_string())?;
+
+    let builder = Builder::new(NOISE_PATTERN.parse().map_err(|e| format!("{:?}", e))?)
+        .local_private_key(&identity.private_key_bytes());
+    let noise = builder
+        .build_initiator()
+        .map_err(|e| format!("Noise handshake init failed: {}", e))?;
+
+    run_tunnel(&mut stream, noise, true, local_payload, is_trusted).await
+}
+
+/// Listen on `addr` and, for each incoming connection, perform the
+/// Noise_XX handshake as the responder, verify the dialing peer against
+/// `is_trusted`, and exchange `SyncPayload`s the same way the initiator
+/// side does. Runs until the listener is dropped or errors.
+pub async fn listen(
+    addr: &str,
+    identity: &DeviceIdentity,
+    build_local_payload: impl Fn() -> Result<SyncPayload, String> + Send + Sync + 'static,
+    is_trusted: impl Fn(&PublicKeyHex) -> bool + Send + Sync + Clone + 'static,
+    on_peer_payload: impl Fn(SyncPayload) -> Result<(), String> + Send + Sync + Clone + 'static,
+) -> Result<(), String> {
+    let listener = TcpListener::bind(addr).await.map_err(|e| e.to_string())?;
+
+    loop {
+        let (mut stream, _peer_addr) = listener.accept().await.map_err(|e| e.to_string())?;
+        let private_key = identity.private_key_bytes();
+        let is_trusted = is_trusted.clone();
+        let on_peer_payload = on_peer_payload.clone();
+        let local_payload = build_local_payload()?;
+
+        tokio::spawn(async move {
+            let builder = match Builder::new(NOISE_PATTERN.parse().expect("static pattern parses"))
+                .local_private_key(&private_key)
+                .build_responder()
+            {
+                Ok(noise) => noise,
+                Err(e) => {
+                    log::warn!("[P2P] Noise responder init failed: {}", e);
+                    return;
+                }
+            };
+
+            match run_tunnel(&mut stream, builder, false, local_payload, is_trusted).await {
+                Ok(peer_payload) => {
+                    if let Err(e) = on_peer_payload(peer_payload) {
+                        log::warn!("[P2P] Failed to apply inbound sync payload: {}", e);
+                    }
+                }
+                Err(e) => log::warn!("[P2P] Inbound sync tunnel failed: {}", e),
+            }
+        });
+    }
+}
+
+/// Run the handshake (either role) to completion, verify the peer's static
+/// key is trusted before sending anything sensitive, then exchange
+/// payloads over the resulting transport.
+async fn run_tunnel(
+    stream: &mut TcpStream,
+    mut noise: snow::HandshakeState,
+    is_initiator: bool,
+    local_payload: SyncPayload,
+    is_trusted: impl Fn(&PublicKeyHex) -> bool,
+) -> Result<SyncPayload, String> {
+    handshake(stream, &mut noise, is_initiator).await?;
+
+    let remote_static = noise
+        .get_remote_static()
+        .ok_or_else(|| "Noise handshake completed without a remote static key".to_string())?;
+    let remote_key_hex = hex::encode(remote_static);
+    if !is_trusted(&remote_key_hex) {
+        return Err(format!(
+            "Refusing to sync with untrusted peer {} - pair with it first",
+            remote_key_hex
+        ));
+    }
+
+    let mut transport = noise
+        .into_transport_mode()
+        .map_err(|e| format!("Failed to enter transport mode: {}", e))?;
+
+    let outgoing = serde_json::to_vec(&local_payload).map_err(|e| e.to_string())?;
+    send_encrypted(stream, &mut transport, &outgoing).await?;
+
+    let incoming = recv_encrypted(stream, &mut transport).await?;
+    serde_json::from_slice(&incoming).map_err(|e| e.to_string())
+}
+
+async fn handshake(
+    stream: &mut TcpStream,
+    noise: &mut snow::HandshakeState,
+    is_initiator: bool,
+) -> Result<(), String> {
+    let mut buf = [0u8; 1024];
+
+    if is_initiator {
+        // -> e
+        let len = noise.write_message(&[], &mut buf).map_err(|e| e.to_string())?;
+        write_frame(stream, &buf[..len]).await?;
+
+        // <- e, ee, s, es
+        let msg = read_frame(stream).await?;
+        noise.read_message(&msg, &mut buf).map_err(|e| e.to_string())?;
+
+        // -> s, se
+        let len = noise.write_message(&[], &mut buf).map_err(|e| e.to_string())?;
+        write_frame(stream, &buf[..len]).await?;
+    } else {
+        // <- e
+        let msg = read_frame(stream).await?;
+        noise.read_message(&msg, &mut buf).map_err(|e| e.to_string())?;
+
+        // -> e, ee, s, es
+        let len = noise.write_message(&[], &mut buf).map_err(|e| e.to_string())?;
+        write_frame(stream, &buf[..len]).await?;
+
+        // <- s, se
+        let msg = read_frame(stream).await?;
+        noise.read_message(&msg, &mut buf).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+async fn send_encrypted(
+    stream: &mut TcpStream,
+    transport: &mut snow::TransportState,
+    plaintext: &[u8],
+) -> Result<(), String> {
+    let mut buf = vec![0u8; plaintext.len() + 16];
+    let len = transport
+        .write_message(plaintext, &mut buf)
+        .map_err(|e| e.to_string())?;
+    write_frame(stream, &buf[..len]).await
+}
+
+async fn recv_encrypted(
+    stream: &mut TcpStream,
+    transport: &mut snow::TransportState,
+) -> Result<Vec<u8>, String> {
+    let ciphertext = read_frame(stream).await?;
+    let mut buf = vec![0u8; ciphertext.len()];
+    let len = transport
+        .read_message(&ciphertext, &mut buf)
+        .map_err(|e| e.to_string())?;
+    buf.truncate(len);
+    Ok(buf)
+}
+
+async fn write_frame(stream: &mut TcpStream, data: &[u8]) -> Result<(), String> {
+    stream
+        .write_all(&(data.len() as u32).to_be_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+    stream.write_all(data).await.map_err(|e| e.to_string())
+}
+
+async fn read_frame(stream: &mut TcpStream) -> Result<Vec<u8>, String> {
+    let mut len_buf = [0u8; 4];
+    stream.read_exact(&mut len_buf).await.map_err(|e| e.to_string())?;
+    let len = u32::from_be_bytes(len_buf) as usize;
+
+    let mut buf = vec![0u8; len];
+    stream.read_exact(&mut buf).await.map_err(|e| e.to_string())?;
+    Ok(buf)
+}
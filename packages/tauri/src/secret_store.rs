@@ -0,0 +1,213 @@
+//! Secret storage with two backends: the OS keyring (primary) and an
+//! encrypted-at-rest file vault used when the keyring isn't usable (e.g.
+//! headless Linux with no Secret Service running). `storage::set_secret`
+//! previously logged a "base64 fallback" that didn't exist; this is that
+//! fallback, for real, with a real cipher rather than base64.
+
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+const SERVICE_NAME: &str = "sbtltv";
+
+pub trait SecretBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn set(&self, key: &str, value: &str) -> Result<(), String>;
+    fn get(&self, key: &str) -> Option<String>;
+    fn delete(&self, key: &str);
+}
+
+struct KeyringBackend;
+
+impl SecretBackend for KeyringBackend {
+    fn name(&self) -> &'static str {
+        "keyring"
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        keyring::Entry::new(SERVICE_NAME, key)
+            .map_err(|e| e.to_string())?
+            .set_password(value)
+            .map_err(|e| e.to_string())
+    }
+
+    fn get(&self, key: &str) -> Option<String> {
+        keyring::Entry::new(SERVICE_NAME, key)
+            .ok()
+            .and_then(|entry| entry.get_password().ok())
+    }
+
+    fn delete(&self, key: &str) {
+        if let Ok(entry) = keyring::Entry::new(SERVICE_NAME, key) {
+            let _ = entry.delete_credential();
+        }
+    }
+}
+
+/// Checks whether the keyring actually works (not just whether the API
+/// call succeeds - some headless setups accept writes and silently drop
+/// them), by round-tripping a throwaway entry.
+fn keyring_is_usable() -> bool {
+    let probe = keyring::Entry::new(SERVICE_NAME, "vault-probe");
+    match probe {
+        Ok(entry) => {
+            let ok = entry.set_password("probe").is_ok() && entry.get_password().is_ok();
+            let _ = entry.delete_credential();
+            ok
+        }
+        Err(_) => false,
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct VaultEntry {
+    nonce: [u8; 12],
+    ciphertext: Vec<u8>,
+}
+
+/// Encrypted-at-rest fallback: a random data key lives in a sibling
+/// `sbtltv-keyvault.key` file (0600), and each secret is stored in
+/// `secrets.enc` encrypted with that key under its own random nonce.
+struct FileVaultBackend {
+    data_key: [u8; 32],
+    secrets_path: PathBuf,
+    entries: Mutex<HashMap<String, VaultEntry>>,
+}
+
+impl FileVaultBackend {
+    fn new(data_dir: &Path) -> Result<Self, String> {
+        let key_path = data_dir.join("sbtltv-keyvault.key");
+        let data_key = load_or_create_data_key(&key_path)?;
+
+        let secrets_path = data_dir.join("secrets.enc");
+        let entries = if secrets_path.exists() {
+            let bytes = fs::read(&secrets_path).map_err(|e| e.to_string())?;
+            serde_json::from_slice(&bytes).unwrap_or_default()
+        } else {
+            HashMap::new()
+        };
+
+        Ok(Self { data_key, secrets_path, entries: Mutex::new(entries) })
+    }
+
+    fn persist(&self, entries: &HashMap<String, VaultEntry>) -> Result<(), String> {
+        let bytes = serde_json::to_vec(entries).map_err(|e| e.to_string())?;
+        fs::write(&self.secrets_path, bytes).map_err(|e| e.to_string())?;
+        restrict_permissions(&self.secrets_path);
+        Ok(())
+    }
+}
+
+fn load_or_create_data_key(path: &Path) -> Result<[u8; 32], String> {
+    if path.exists() {
+        let bytes = fs::read(path).map_err(|e| e.to_string())?;
+        let key: [u8; 32] = bytes.try_into().map_err(|_| "corrupt vault key".to_string())?;
+        return Ok(key);
+    }
+
+    let mut key = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut key);
+    fs::write(path, key).map_err(|e| e.to_string())?;
+    restrict_permissions(path);
+    Ok(key)
+}
+
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    if let Ok(meta) = fs::metadata(path) {
+        let mut perms = meta.permissions();
+        perms.set_mode(0o600);
+        let _ = fs::set_permissions(path, perms);
+    }
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) {
+    // Windows ACLs are inherited from the app data directory; nothing
+    // equivalent to chmod 0600 to apply here.
+}
+
+impl SecretBackend for FileVaultBackend {
+    fn name(&self) -> &'static str {
+        "encrypted-file-vault"
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        let cipher = ChaCha20Poly1305::new(&self.data_key.into());
+        let mut nonce_bytes = [0u8; 12];
+        rand::thread_rng().fill_bytes(&mut nonce_bytes);
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce_bytes), value.as_bytes())
+            .map_err(|e| format!("Vault encryption failed: {}", e))?;
+
+        let mut entries = self.entries.lock().map_err(|e| e.to_string())?;
+        entries.insert(key.to_string(), VaultEntry { nonce: nonce_bytes, ciphertext });
+        self.persist(&entries)
+    }
+
+    fn get(&self, key: &str) -> Option<String> {
+        let entries = self.entries.lock().ok()?;
+        let entry = entries.get(key)?;
+        let cipher = ChaCha20Poly1305::new(&self.data_key.into());
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(&entry.nonce), entry.ciphertext.as_ref())
+            .ok()?;
+        String::from_utf8(plaintext).ok()
+    }
+
+    fn delete(&self, key: &str) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.remove(key);
+            let _ = self.persist(&entries);
+        }
+    }
+}
+
+static BACKEND: OnceLock<Box<dyn SecretBackend>> = OnceLock::new();
+
+/// Choose the backend at startup: keyring if it actually round-trips a
+/// probe value, otherwise the encrypted file vault next to the config.
+pub fn init(data_dir: &Path) {
+    let backend: Box<dyn SecretBackend> = if keyring_is_usable() {
+        Box::new(KeyringBackend)
+    } else {
+        match FileVaultBackend::new(data_dir) {
+            Ok(vault) => Box::new(vault),
+            Err(e) => {
+                log::error!("[SECRETS] Failed to init file vault, secrets will not persist: {}", e);
+                Box::new(KeyringBackend) // best-effort; set/get will simply fail
+            }
+        }
+    };
+
+    log::info!("[SECRETS] Using backend: {}", backend.name());
+    let _ = BACKEND.set(backend);
+}
+
+fn backend() -> &'static dyn SecretBackend {
+    BACKEND.get().map(|b| b.as_ref()).unwrap_or(&KeyringBackend)
+}
+
+pub fn set_secret(key: &str, value: &str) -> Result<(), String> {
+    backend().set(key, value)
+}
+
+pub fn get_secret(key: &str) -> Option<String> {
+    backend().get(key)
+}
+
+pub fn delete_secret(key: &str) {
+    backend().delete(key)
+}
+
+/// Name of the currently active backend, so the UI can warn that
+/// file-vault secrets are only as safe as the OS file permissions.
+pub fn active_backend_name() -> &'static str {
+    backend().name()
+}
@@ -0,0 +1,203 @@
+//! Lock-free shared-memory frame ring - sbtlTV's last-resort video path
+//! for machines where `mpv::gl_context::HeadlessGLContext` can't get a GPU
+//! context at all (the surfman device-creation failure already
+//! panic-caught in `gl_context.rs`). See
+//! `mpv::run_software_fallback` for the producer that drives this when
+//! that happens.
+//!
+//! Unlike `mpv`'s `FrameRingBuffer` - which still hands a filled segment
+//! off to the frontend via a Tauri event - every slot here carries its own
+//! metadata header inside the shared-memory segment itself, seqlock-style,
+//! so the producer never blocks on a lock and a consumer never needs an
+//! event round-trip to know a slot's width/height/stride/pts: it reads the
+//! header directly out of the mapping, copies the payload, then re-reads
+//! the header to check the frame wasn't torn.
+
+use serde::Serialize;
+use shared_memory::{Shmem, ShmemConf};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+/// Slot count is fixed and a power of two so `index % SLOT_COUNT` is a
+/// cheap mask rather than a division.
+const SLOT_COUNT: usize = 4;
+
+/// Per-slot layout at the front of each slot's region in the segment.
+/// `seq` is a seqlock counter: odd while the producer is mid-write, even
+/// once a write has landed. A consumer reads `seq`, copies the payload,
+/// then re-reads `seq` - if either read caught an odd value or the two
+/// don't match, the frame was torn mid-copy and should be retried or
+/// skipped rather than displayed.
+#[repr(C)]
+struct SlotHeader {
+    seq: AtomicU32,
+    width: u32,
+    height: u32,
+    stride: u32,
+    pts_micros: i64,
+}
+
+const SLOT_HEADER_SIZE: usize = std::mem::size_of::<SlotHeader>();
+
+/// Layout at the very front of the segment, ahead of the slots.
+#[repr(C)]
+struct RingHeader {
+    slot_count: u32,
+    slot_payload_size: u32,
+    /// Monotonically increasing; `write_index % SLOT_COUNT` names the
+    /// slot a consumer should treat as newest once it stops changing.
+    write_index: AtomicU64,
+}
+
+const RING_HEADER_SIZE: usize = std::mem::size_of::<RingHeader>();
+
+/// Everything a consumer (the frontend, via a native plugin, or another
+/// process) needs to map the ring read-only and walk its slots.
+#[derive(Clone, Serialize)]
+pub struct ShmRingHandle {
+    pub os_id: String,
+    pub slot_count: u32,
+    pub slot_payload_size: u32,
+}
+
+/// Owns the shared-memory segment and writes frames into it. `write_frame`
+/// never allocates and never blocks on a lock - the producer always
+/// claims the next slot in the ring, so a consumer falling behind just
+/// means that slot's previous frame gets overwritten, i.e. the oldest
+/// frame is dropped.
+pub struct ShmFrameRing {
+    shmem: Shmem,
+    handle: ShmRingHandle,
+}
+
+// Safety: the mapping is written only by the single producer thread that
+// owns this `ShmFrameRing`; other readers (this process's frontend, or a
+// separate process) only ever read through the seqlock protocol in
+// `SlotHeader`, never through this type.
+unsafe impl Send for ShmFrameRing {}
+
+impl ShmFrameRing {
+    fn create(slot_payload_size: usize) -> Result<Self, String> {
+        let os_id = format!("sbtltv-shm-ring-{}", std::process::id());
+        let total_size = RING_HEADER_SIZE + SLOT_COUNT * (SLOT_HEADER_SIZE + slot_payload_size);
+
+        let shmem = ShmemConf::new()
+            .size(total_size)
+            .os_id(&os_id)
+            .create()
+            .map_err(|e| format!("Failed to create shm frame ring {}: {}", os_id, e))?;
+
+        let ring = Self {
+            shmem,
+            handle: ShmRingHandle { os_id, slot_count: SLOT_COUNT as u32, slot_payload_size: slot_payload_size as u32 },
+        };
+
+        // Sole owner at this point (segment was just created), so a plain
+        // write is fine - no consumer can have mapped it yet.
+        let header = ring.ring_header_mut();
+        header.slot_count = SLOT_COUNT as u32;
+        header.slot_payload_size = slot_payload_size as u32;
+        header.write_index.store(0, Ordering::Release);
+
+        Ok(ring)
+    }
+
+    fn base_ptr(&self) -> *mut u8 {
+        unsafe { self.shmem.as_ptr() }
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    fn ring_header_mut(&self) -> &mut RingHeader {
+        unsafe { &mut *(self.base_ptr() as *mut RingHeader) }
+    }
+
+    fn slot_ptr(&self, index: usize) -> *mut u8 {
+        let offset = RING_HEADER_SIZE + index * (SLOT_HEADER_SIZE + self.handle.slot_payload_size as usize);
+        unsafe { self.base_ptr().add(offset) }
+    }
+
+    pub fn handle(&self) -> &ShmRingHandle {
+        &self.handle
+    }
+
+    /// Write one frame into the next slot, overwriting whatever frame
+    /// previously lived there.
+    fn write_frame(&mut self, width: u32, height: u32, stride: u32, pts_micros: i64, pixels: &[u8]) {
+        let write_index = self.ring_header_mut().write_index.fetch_add(1, Ordering::SeqCst);
+        let slot = (write_index as usize) % SLOT_COUNT;
+
+        unsafe {
+            let slot_base = self.slot_ptr(slot);
+            let header = &mut *(slot_base as *mut SlotHeader);
+
+            // Mark the slot "being written" before touching the payload so
+            // a concurrent reader mid-copy can tell to retry.
+            header.seq.fetch_add(1, Ordering::AcqRel);
+
+            let payload = std::slice::from_raw_parts_mut(slot_base.add(SLOT_HEADER_SIZE), self.handle.slot_payload_size as usize);
+            let n = payload.len().min(pixels.len());
+            payload[..n].copy_from_slice(&pixels[..n]);
+
+            header.width = width;
+            header.height = height;
+            header.stride = stride;
+            header.pts_micros = pts_micros;
+            // Even again: the write is complete and safe to read.
+            header.seq.fetch_add(1, Ordering::Release);
+        }
+    }
+}
+
+/// Tauri-managed wrapper: lazily creates the ring sized for the first
+/// frame it sees, and recreates it (at the new, larger size) if a later
+/// frame no longer fits - mirroring `FrameRingBuffer::resize_if_needed` in
+/// the GPU path.
+pub struct ShmRingState {
+    ring: Mutex<Option<ShmFrameRing>>,
+}
+
+impl ShmRingState {
+    pub fn new() -> Self {
+        Self { ring: Mutex::new(None) }
+    }
+
+    pub fn write_frame(&self, width: u32, height: u32, pts_micros: i64, pixels: &[u8]) -> Result<(), String> {
+        let stride = width * 4;
+        let payload_size = (stride * height) as usize;
+
+        let mut guard = self.ring.lock().unwrap();
+        let needs_new = match guard.as_ref() {
+            Some(ring) => (ring.handle().slot_payload_size as usize) < payload_size,
+            None => true,
+        };
+        if needs_new {
+            *guard = Some(ShmFrameRing::create(payload_size)?);
+        }
+        guard.as_mut().unwrap().write_frame(width, height, stride, pts_micros, pixels);
+        Ok(())
+    }
+
+    pub fn handle(&self) -> Option<ShmRingHandle> {
+        self.ring.lock().unwrap().as_ref().map(|ring| ring.handle().clone())
+    }
+}
+
+impl Default for ShmRingState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn init(app: &AppHandle) {
+    app.manage(ShmRingState::new());
+}
+
+/// Acquire the running software-fallback ring's shm handle, so a consumer
+/// can map it read-only. Errs if the fallback path hasn't produced a
+/// frame yet (e.g. the GPU path is working fine and this ring was never
+/// needed).
+#[tauri::command]
+pub fn shm_ring_get_handle(state: State<ShmRingState>) -> Result<ShmRingHandle, String> {
+    state.handle().ok_or_else(|| "Software frame ring is not running".to_string())
+}
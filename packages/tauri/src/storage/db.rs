@@ -0,0 +1,239 @@
+//! SQLite schema, migrations, and row mapping backing `StoreData`. This
+//! replaced a single `sbtltv-config.json` blob that got rewritten whole on
+//! every save; sources and settings are now separate tables written in one
+//! transaction, with room to add EPG/VOD cache tables alongside them.
+//! Secrets never touch this database - they stay in `secret_store`.
+
+use super::{StoreData, StoredSettings, StoredSource};
+use rusqlite::Connection;
+
+const CURRENT_SCHEMA_VERSION: i64 = 3;
+
+pub(super) fn open(path: &std::path::Path) -> Result<Connection, String> {
+    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+    conn.pragma_update(None, "journal_mode", "WAL")
+        .map_err(|e| e.to_string())?;
+    migrate(&conn)?;
+    Ok(conn)
+}
+
+fn migrate(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_version (version INTEGER NOT NULL)",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let version: i64 = conn
+        .query_row("SELECT version FROM schema_version LIMIT 1", [], |row| row.get(0))
+        .unwrap_or(0);
+
+    if version < 1 {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS sources (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                type TEXT NOT NULL,
+                url TEXT NOT NULL,
+                enabled INTEGER NOT NULL,
+                epg_url TEXT,
+                auto_load_epg INTEGER,
+                username TEXT
+            );
+            CREATE TABLE IF NOT EXISTS settings (
+                id INTEGER PRIMARY KEY CHECK (id = 0),
+                theme TEXT NOT NULL,
+                last_source_id TEXT,
+                vod_refresh_hours INTEGER,
+                epg_refresh_hours INTEGER,
+                movie_genres_enabled TEXT,
+                series_genres_enabled TEXT,
+                rpdb_backdrops_enabled INTEGER,
+                allow_lan_sources INTEGER,
+                window_state TEXT
+            );
+            CREATE TABLE IF NOT EXISTS meta (
+                id INTEGER PRIMARY KEY CHECK (id = 0),
+                revision INTEGER NOT NULL
+            );",
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    if version < 2 {
+        conn.execute_batch("ALTER TABLE settings ADD COLUMN remote_control_enabled INTEGER")
+            .map_err(|e| e.to_string())?;
+    }
+
+    if version < 3 {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS p2p_trusted_peers (public_key TEXT PRIMARY KEY)",
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    // Future schema bumps (EPG/VOD cache tables, etc.) add another
+    // `if version < N` block above this and land here.
+    if version < CURRENT_SCHEMA_VERSION {
+        conn.execute("DELETE FROM schema_version", [])
+            .map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT INTO schema_version (version) VALUES (?1)",
+            [CURRENT_SCHEMA_VERSION],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+pub(super) fn load(conn: &Connection) -> Result<StoreData, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, type, url, enabled, epg_url, auto_load_epg, username FROM sources",
+        )
+        .map_err(|e| e.to_string())?;
+    let sources = stmt
+        .query_map([], |row| {
+            Ok(StoredSource {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                source_type: row.get(2)?,
+                url: row.get(3)?,
+                enabled: row.get(4)?,
+                epg_url: row.get(5)?,
+                auto_load_epg: row.get(6)?,
+                username: row.get(7)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let settings = conn
+        .query_row(
+            "SELECT theme, last_source_id, vod_refresh_hours, epg_refresh_hours,
+                    movie_genres_enabled, series_genres_enabled, rpdb_backdrops_enabled,
+                    allow_lan_sources, window_state, remote_control_enabled
+             FROM settings WHERE id = 0",
+            [],
+            |row| {
+                let movie_genres: Option<String> = row.get(4)?;
+                let series_genres: Option<String> = row.get(5)?;
+                let window_state: Option<String> = row.get(8)?;
+                Ok(StoredSettings {
+                    theme: row.get(0)?,
+                    last_source_id: row.get(1)?,
+                    vod_refresh_hours: row.get(2)?,
+                    epg_refresh_hours: row.get(3)?,
+                    movie_genres_enabled: movie_genres.and_then(|s| serde_json::from_str(&s).ok()),
+                    series_genres_enabled: series_genres
+                        .and_then(|s| serde_json::from_str(&s).ok()),
+                    rpdb_backdrops_enabled: row.get(6)?,
+                    allow_lan_sources: row.get(7)?,
+                    window_state: window_state.and_then(|s| serde_json::from_str(&s).ok()),
+                    remote_control_enabled: row.get(9)?,
+                })
+            },
+        )
+        .unwrap_or_default();
+
+    let revision: i64 = conn
+        .query_row("SELECT revision FROM meta WHERE id = 0", [], |row| row.get(0))
+        .unwrap_or(0);
+
+    let mut peers_stmt = conn
+        .prepare("SELECT public_key FROM p2p_trusted_peers")
+        .map_err(|e| e.to_string())?;
+    let trusted_peers = peers_stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(StoreData { sources, settings, revision: revision as u64, trusted_peers })
+}
+
+/// Writes every table in one transaction. Sources are replaced wholesale
+/// rather than diffed row-by-row - simpler, and still strictly cheaper and
+/// more atomic than rewriting the entire config file, which is the
+/// liability this replaced.
+pub(super) fn save(conn: &Connection, data: &StoreData) -> Result<(), String> {
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+
+    tx.execute("DELETE FROM sources", []).map_err(|e| e.to_string())?;
+    for s in &data.sources {
+        tx.execute(
+            "INSERT INTO sources (id, name, type, url, enabled, epg_url, auto_load_epg, username)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                s.id,
+                s.name,
+                s.source_type,
+                s.url,
+                s.enabled,
+                s.epg_url,
+                s.auto_load_epg,
+                s.username,
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    let settings = &data.settings;
+    tx.execute(
+        "INSERT INTO settings (id, theme, last_source_id, vod_refresh_hours, epg_refresh_hours,
+                                movie_genres_enabled, series_genres_enabled,
+                                rpdb_backdrops_enabled, allow_lan_sources, window_state,
+                                remote_control_enabled)
+         VALUES (0, ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+         ON CONFLICT(id) DO UPDATE SET
+            theme = excluded.theme,
+            last_source_id = excluded.last_source_id,
+            vod_refresh_hours = excluded.vod_refresh_hours,
+            epg_refresh_hours = excluded.epg_refresh_hours,
+            movie_genres_enabled = excluded.movie_genres_enabled,
+            series_genres_enabled = excluded.series_genres_enabled,
+            rpdb_backdrops_enabled = excluded.rpdb_backdrops_enabled,
+            allow_lan_sources = excluded.allow_lan_sources,
+            window_state = excluded.window_state,
+            remote_control_enabled = excluded.remote_control_enabled",
+        rusqlite::params![
+            settings.theme,
+            settings.last_source_id,
+            settings.vod_refresh_hours,
+            settings.epg_refresh_hours,
+            settings
+                .movie_genres_enabled
+                .as_ref()
+                .map(|v| serde_json::to_string(v).unwrap_or_default()),
+            settings
+                .series_genres_enabled
+                .as_ref()
+                .map(|v| serde_json::to_string(v).unwrap_or_default()),
+            settings.rpdb_backdrops_enabled,
+            settings.allow_lan_sources,
+            settings
+                .window_state
+                .as_ref()
+                .map(|v| serde_json::to_string(v).unwrap_or_default()),
+            settings.remote_control_enabled,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    tx.execute(
+        "INSERT INTO meta (id, revision) VALUES (0, ?1)
+         ON CONFLICT(id) DO UPDATE SET revision = excluded.revision",
+        [data.revision as i64],
+    )
+    .map_err(|e| e.to_string())?;
+
+    tx.execute("DELETE FROM p2p_trusted_peers", []).map_err(|e| e.to_string())?;
+    for key in &data.trusted_peers {
+        tx.execute("INSERT INTO p2p_trusted_peers (public_key) VALUES (?1)", [key])
+            .map_err(|e| e.to_string())?;
+    }
+
+    tx.commit().map_err(|e| e.to_string())
+}
@@ -1,11 +1,10 @@
+mod db;
+
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
 use std::sync::Mutex;
 use tauri::{AppHandle, Manager};
 
-const SERVICE_NAME: &str = "sbtltv";
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Source {
     pub id: String,
@@ -33,6 +32,7 @@ pub struct AppSettings {
     pub poster_db_api_key: Option<String>,
     pub rpdb_backdrops_enabled: Option<bool>,
     pub allow_lan_sources: Option<bool>,
+    pub remote_control_enabled: Option<bool>,
 }
 
 impl Default for AppSettings {
@@ -48,6 +48,7 @@ impl Default for AppSettings {
             poster_db_api_key: None,
             rpdb_backdrops_enabled: Some(false),
             allow_lan_sources: Some(false),
+            remote_control_enabled: Some(false),
         }
     }
 }
@@ -67,17 +68,32 @@ struct StoredSource {
     // Password stored in OS keyring, not in JSON
 }
 
+/// Saved window geometry, persisted so the player reopens where the user
+/// left it (see `window_cmds::window_save_state`/`window_restore_state`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowState {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+    pub fullscreen: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct StoredSettings {
     theme: String,
     last_source_id: Option<String>,
-    vod_refresh_hours: Option<u32>,
-    epg_refresh_hours: Option<u32>,
-    movie_genres_enabled: Option<Vec<u32>>,
-    series_genres_enabled: Option<Vec<u32>>,
+    pub(crate) vod_refresh_hours: Option<u32>,
+    pub(crate) epg_refresh_hours: Option<u32>,
+    pub(crate) movie_genres_enabled: Option<Vec<u32>>,
+    pub(crate) series_genres_enabled: Option<Vec<u32>>,
     rpdb_backdrops_enabled: Option<bool>,
     pub(crate) allow_lan_sources: Option<bool>,
+    pub(crate) remote_control_enabled: Option<bool>,
+    pub(crate) window_state: Option<WindowState>,
     // Sensitive keys stored in OS keyring
 }
 
@@ -92,6 +108,8 @@ impl Default for StoredSettings {
             series_genres_enabled: None,
             rpdb_backdrops_enabled: Some(false),
             allow_lan_sources: Some(false),
+            remote_control_enabled: Some(false),
+            window_state: None,
         }
     }
 }
@@ -100,6 +118,15 @@ impl Default for StoredSettings {
 pub(crate) struct StoreData {
     sources: Vec<StoredSource>,
     pub(crate) settings: StoredSettings,
+    /// Monotonically increasing counter bumped on every local write,
+    /// used by the P2P sync subsystem for last-write-wins reconciliation.
+    #[serde(default)]
+    pub(crate) revision: u64,
+    /// Public keys of devices paired via `p2p::p2p_pair_with_code`,
+    /// trusted to open a sync tunnel without re-pairing. Persisted so a
+    /// restart doesn't forget every pairing (see `p2p::P2pState`).
+    #[serde(default)]
+    trusted_peers: Vec<String>,
 }
 
 impl Default for StoreData {
@@ -107,48 +134,137 @@ impl Default for StoreData {
         Self {
             sources: Vec::new(),
             settings: StoredSettings::default(),
+            revision: 0,
+            trusted_peers: Vec::new(),
+        }
+    }
+}
+
+/// Just enough of a source's identity to address its keyring secret
+/// without exposing the rest of `StoredSource` outside this module.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceIdentity {
+    pub id: String,
+    pub source_type: String,
+}
+
+/// The full non-secret contents of a source, for the P2P sync path
+/// (`crate::p2p`) to carry across the tunnel and merge into the local
+/// source list. Excludes `password`, which travels separately as a
+/// `SyncedSecret` straight into the keyring.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncedSource {
+    pub id: String,
+    pub name: String,
+    pub source_type: String,
+    pub url: String,
+    pub enabled: bool,
+    pub epg_url: Option<String>,
+    pub auto_load_epg: Option<bool>,
+    pub username: Option<String>,
+}
+
+impl From<&StoredSource> for SyncedSource {
+    fn from(s: &StoredSource) -> Self {
+        SyncedSource {
+            id: s.id.clone(),
+            name: s.name.clone(),
+            source_type: s.source_type.clone(),
+            url: s.url.clone(),
+            enabled: s.enabled,
+            epg_url: s.epg_url.clone(),
+            auto_load_epg: s.auto_load_epg,
+            username: s.username.clone(),
+        }
+    }
+}
+
+impl From<SyncedSource> for StoredSource {
+    fn from(s: SyncedSource) -> Self {
+        StoredSource {
+            id: s.id,
+            name: s.name,
+            source_type: s.source_type,
+            url: s.url,
+            enabled: s.enabled,
+            epg_url: s.epg_url,
+            auto_load_epg: s.auto_load_epg,
+            username: s.username,
+        }
+    }
+}
+
+impl StoreData {
+    pub(crate) fn source_identities(&self) -> Vec<SourceIdentity> {
+        self.sources
+            .iter()
+            .map(|s| SourceIdentity {
+                id: s.id.clone(),
+                source_type: s.source_type.clone(),
+            })
+            .collect()
+    }
+
+    /// Full source contents for the P2P sync path, see `SyncedSource`.
+    pub(crate) fn synced_sources(&self) -> Vec<SyncedSource> {
+        self.sources.iter().map(SyncedSource::from).collect()
+    }
+
+    /// Merge an incoming source list: an id already present locally is
+    /// overwritten with the incoming copy, an unknown id is inserted as a
+    /// new source. Callers gate on the top-level `StoreData::revision`
+    /// before calling this, so "incoming" here already means "newer".
+    pub(crate) fn merge_sources(&mut self, incoming: Vec<SyncedSource>) {
+        for synced in incoming {
+            match self.sources.iter_mut().find(|s| s.id == synced.id) {
+                Some(existing) => *existing = synced.into(),
+                None => self.sources.push(synced.into()),
+            }
+        }
+    }
+
+    pub(crate) fn trusted_peers(&self) -> Vec<String> {
+        self.trusted_peers.clone()
+    }
+
+    pub(crate) fn add_trusted_peer(&mut self, public_key: String) {
+        if !self.trusted_peers.contains(&public_key) {
+            self.trusted_peers.push(public_key);
         }
     }
 }
 
 pub struct StorageState {
-    data_path: PathBuf,
+    conn: Mutex<rusqlite::Connection>,
     pub(crate) data: Mutex<StoreData>,
 }
 
 impl StorageState {
-    fn save(&self) -> Result<(), String> {
+    /// Writes the in-memory snapshot through to SQLite inside one
+    /// transaction (see `db::save`) - atomic across the sources and
+    /// settings tables, unlike the single-file blob rewrite this replaced.
+    pub(crate) fn save(&self) -> Result<(), String> {
         let data = self.data.lock().map_err(|e| e.to_string())?;
-        let json = serde_json::to_string_pretty(&*data).map_err(|e| e.to_string())?;
-        fs::write(&self.data_path, json).map_err(|e| e.to_string())?;
-        Ok(())
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        db::save(&conn, &data)
     }
 }
 
-fn keyring_key(id: &str, field: &str) -> String {
+pub(crate) fn keyring_key(id: &str, field: &str) -> String {
     format!("{}:{}", id, field)
 }
 
-fn set_secret(key: &str, value: &str) -> Result<(), String> {
-    match keyring::Entry::new(SERVICE_NAME, key) {
-        Ok(entry) => entry.set_password(value).map_err(|e| {
-            log::warn!("Keyring set failed for {}: {}, using base64 fallback", key, e);
-            e.to_string()
-        }),
-        Err(e) => Err(e.to_string()),
-    }
+pub(crate) fn set_secret(key: &str, value: &str) -> Result<(), String> {
+    crate::secret_store::set_secret(key, value)
 }
 
-fn get_secret(key: &str) -> Option<String> {
-    keyring::Entry::new(SERVICE_NAME, key)
-        .ok()
-        .and_then(|entry| entry.get_password().ok())
+pub(crate) fn get_secret(key: &str) -> Option<String> {
+    crate::secret_store::get_secret(key)
 }
 
 fn delete_secret(key: &str) {
-    if let Ok(entry) = keyring::Entry::new(SERVICE_NAME, key) {
-        let _ = entry.delete_credential();
-    }
+    crate::secret_store::delete_secret(key)
 }
 
 pub fn init(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
@@ -158,22 +274,33 @@ pub fn init(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         .expect("Failed to get app data dir");
     fs::create_dir_all(&app_data)?;
 
-    let data_path = app_data.join("sbtltv-config.json");
-    let data: StoreData = if data_path.exists() {
-        let content = fs::read_to_string(&data_path)?;
-        serde_json::from_str(&content).unwrap_or_default()
+    crate::secret_store::init(&app_data);
+
+    let db_path = app_data.join("sbtltv.db");
+    let is_first_run = !db_path.exists();
+    let conn = db::open(&db_path)?;
+
+    let data = if is_first_run {
+        // Nothing in SQLite yet: pull in the old JSON config (if this
+        // install predates the database) or the Electron config, then
+        // persist it as the first set of rows.
+        let json_path = app_data.join("sbtltv-config.json");
+        let imported = if json_path.exists() {
+            fs::read_to_string(&json_path)
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+        } else {
+            try_migrate_electron_data()
+        };
+        let data = imported.unwrap_or_default();
+        db::save(&conn, &data)?;
+        data
     } else {
-        // Try migrating from Electron config
-        let migrated = try_migrate_electron_data();
-        if let Some(ref migrated_data) = migrated {
-            let json = serde_json::to_string_pretty(migrated_data)?;
-            fs::write(&data_path, json)?;
-        }
-        migrated.unwrap_or_default()
+        db::load(&conn)?
     };
 
     app.manage(StorageState {
-        data_path,
+        conn: Mutex::new(conn),
         data: Mutex::new(data),
     });
 
@@ -380,6 +507,7 @@ pub fn save_source(source: Source, state: tauri::State<StorageState>) -> Storage
     } else {
         data.sources.push(stored);
     }
+    data.revision += 1;
 
     drop(data);
     if let Err(e) = state.save() {
@@ -400,6 +528,7 @@ pub fn delete_source(id: String, state: tauri::State<StorageState>) -> StorageRe
     delete_secret(&keyring_key(&id, "password"));
 
     data.sources.retain(|s| s.id != id);
+    data.revision += 1;
 
     drop(data);
     if let Err(e) = state.save() {
@@ -431,6 +560,7 @@ pub fn get_settings(state: tauri::State<StorageState>) -> StorageResult<AppSetti
         poster_db_api_key,
         rpdb_backdrops_enabled: s.rpdb_backdrops_enabled,
         allow_lan_sources: s.allow_lan_sources,
+        remote_control_enabled: s.remote_control_enabled,
     })
 }
 
@@ -490,6 +620,10 @@ pub fn update_settings(
     if let Some(v) = settings.get("allowLanSources").and_then(|v| v.as_bool()) {
         data.settings.allow_lan_sources = Some(v);
     }
+    if let Some(v) = settings.get("remoteControlEnabled").and_then(|v| v.as_bool()) {
+        data.settings.remote_control_enabled = Some(v);
+    }
+    data.revision += 1;
 
     drop(data);
     if let Err(e) = state.save() {
@@ -501,17 +635,17 @@ pub fn update_settings(
 
 #[tauri::command]
 pub fn is_encryption_available() -> StorageResult<bool> {
-    // Check if keyring is functional
-    let available = keyring::Entry::new(SERVICE_NAME, "test-availability")
-        .map(|entry| {
-            let _ = entry.set_password("test");
-            let result = entry.get_password().is_ok();
-            let _ = entry.delete_credential();
-            result
-        })
-        .unwrap_or(false);
+    // Both the keyring and the encrypted file-vault fallback count as
+    // "available" - secrets are always encrypted at rest either way.
+    StorageResult::ok(true)
+}
 
-    StorageResult::ok(available)
+/// Which secret backend is actually active ("keyring" or
+/// "encrypted-file-vault"), so the UI can warn that file-vault secrets
+/// are only as safe as the OS file permissions protecting them.
+#[tauri::command]
+pub fn get_secret_backend() -> StorageResult<String> {
+    StorageResult::ok(crate::secret_store::active_backend_name().to_string())
 }
 
 #[tauri::command]
@@ -1,4 +1,5 @@
-use tauri::{AppHandle, Manager};
+use crate::storage::{StorageState, WindowState};
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize};
 
 #[tauri::command]
 pub fn window_minimize(app: AppHandle) -> Result<(), String> {
@@ -38,3 +39,124 @@ pub fn window_set_size(app: AppHandle, width: u32, height: u32) -> Result<(), St
         .set_size(tauri::Size::Physical(tauri::PhysicalSize { width: w, height: h }))
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+pub fn window_get_position(app: AppHandle) -> Result<(i32, i32), String> {
+    let window = app.get_webview_window("main").ok_or("No main window")?;
+    let pos = window.outer_position().map_err(|e| e.to_string())?;
+    Ok((pos.x, pos.y))
+}
+
+#[tauri::command]
+pub fn window_set_position(app: AppHandle, x: i32, y: i32) -> Result<(), String> {
+    let window = app.get_webview_window("main").ok_or("No main window")?;
+    window
+        .set_position(tauri::Position::Physical(PhysicalPosition { x, y }))
+        .map_err(|e| e.to_string())
+}
+
+/// Snapshot position, size, and maximized/fullscreen flags and persist
+/// them so the window reopens where the user left it.
+#[tauri::command]
+pub fn window_save_state(app: AppHandle, storage: tauri::State<StorageState>) -> Result<(), String> {
+    let window = app.get_webview_window("main").ok_or("No main window")?;
+
+    let maximized = window.is_maximized().unwrap_or(false);
+    let fullscreen = window.is_fullscreen().unwrap_or(false);
+    let pos = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.inner_size().map_err(|e| e.to_string())?;
+
+    let state = WindowState {
+        x: pos.x,
+        y: pos.y,
+        width: size.width,
+        height: size.height,
+        maximized,
+        fullscreen,
+    };
+
+    {
+        let mut data = storage.data.lock().map_err(|e| e.to_string())?;
+        data.settings.window_state = Some(state);
+    }
+
+    storage.save()
+}
+
+/// Restore the last saved window geometry, clamping it against the
+/// monitors currently connected so a window saved on a since-disconnected
+/// display doesn't spawn off-screen.
+#[tauri::command]
+pub fn window_restore_state(app: AppHandle, storage: tauri::State<StorageState>) -> Result<(), String> {
+    let state = {
+        let data = storage.data.lock().map_err(|e| e.to_string())?;
+        match data.settings.window_state {
+            Some(s) => s,
+            None => return Ok(()),
+        }
+    };
+
+    let window = app.get_webview_window("main").ok_or("No main window")?;
+    let monitors = window.available_monitors().map_err(|e| e.to_string())?;
+
+    let (x, y, width, height) = clamp_to_monitors(&monitors, state.x, state.y, state.width, state.height);
+
+    window
+        .set_size(tauri::Size::Physical(PhysicalSize { width, height }))
+        .map_err(|e| e.to_string())?;
+    window
+        .set_position(tauri::Position::Physical(PhysicalPosition { x, y }))
+        .map_err(|e| e.to_string())?;
+
+    if state.maximized {
+        window.maximize().map_err(|e| e.to_string())?;
+    }
+    window.set_fullscreen(state.fullscreen).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Clamp a saved rect onto whichever currently-available monitor it
+/// overlaps most; if it overlaps none (e.g. that monitor was
+/// disconnected), pull it fully onto the primary/first monitor instead.
+fn clamp_to_monitors(
+    monitors: &[tauri::Monitor],
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+) -> (i32, i32, u32, u32) {
+    let best = monitors.iter().max_by_key(|m| {
+        let pos = m.position();
+        let size = m.size();
+        let overlap_x = (x + width as i32).min(pos.x + size.width as i32) - x.max(pos.x);
+        let overlap_y = (y + height as i32).min(pos.y + size.height as i32) - y.max(pos.y);
+        overlap_x.max(0) as i64 * overlap_y.max(0) as i64
+    });
+
+    let monitor = match best {
+        Some(m) => m,
+        None => return (x, y, width, height),
+    };
+
+    let pos = monitor.position();
+    let size = monitor.size();
+    let has_overlap = x < pos.x + size.width as i32
+        && x + width as i32 > pos.x
+        && y < pos.y + size.height as i32
+        && y + height as i32 > pos.y;
+
+    let w = width.min(size.width);
+    let h = height.min(size.height);
+
+    if has_overlap {
+        let clamped_x = x.clamp(pos.x, pos.x + size.width as i32 - w as i32);
+        let clamped_y = y.clamp(pos.y, pos.y + size.height as i32 - h as i32);
+        (clamped_x, clamped_y, w, h)
+    } else {
+        // Saved position is entirely off every monitor - re-center on this one.
+        let centered_x = pos.x + (size.width as i32 - w as i32) / 2;
+        let centered_y = pos.y + (size.height as i32 - h as i32) / 2;
+        (centered_x, centered_y, w, h)
+    }
+}